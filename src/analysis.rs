@@ -0,0 +1,68 @@
+//! Command line analysis of a single position, used instead of the UI when started with `--analyse` or
+//! `--eval`.
+
+use std::{sync::mpsc, thread};
+
+use crate::{board::{game_state::GameState, position::Position}, bot::{eval_params::EvalParams, eval_trace::EvalTrace, evaluator::ClassicalEvaluator, iterative_deepening_search::IterativeDeepeningSearch, nnue::{self, NnueEvaluator}, search_limits::SearchLimits, search_observer::SearchEvent, search_options::SearchOptions, Bot}};
+
+/// Search the position in `fen` within `limits`, printing the best [`multi_pv`][SearchOptions::multi_pv] lines after every iteration.
+pub fn analyse(fen: &str, search_options: SearchOptions, eval_params: EvalParams, limits: SearchLimits) -> Result<(), String> {
+    let position = Position::from_fen_str(fen).map_err(|e| format!("invalid fen {}: {:?}", fen, e))?;
+
+    match position.get_game_state() {
+        GameState::Ongoing => {},
+        GameState::Checkmate(winner) => {
+            println!("Checkmate, {} wins", winner.as_str());
+            return Ok(());
+        },
+        GameState::Stalemate => {
+            println!("Stalemate");
+            return Ok(());
+        },
+    }
+
+    let bot = IterativeDeepeningSearch::with_options(NnueEvaluator::new(ClassicalEvaluator::new(eval_params)), true, search_options);
+
+    let (sender, receiver) = mpsc::channel();
+
+    let search = thread::spawn(move || {
+        bot.search(&[position], &limits, &sender)
+    });
+
+    // Ends once the search finishes and drops the sender
+    for event in receiver {
+        match event {
+            SearchEvent::Iteration(info) => println!("{}", info),
+            SearchEvent::BookMove(moove) => println!("Book move: {}", moove.uci_string()),
+        }
+    }
+
+    let result = search.join().map_err(|_| "search panicked".to_owned())?;
+
+    println!("Best move: {} ({}) depth {}/{} nodes {} time {:.3}s", result.best_move.uci_string(), result.score, result.stats.depth, result.stats.seldepth, result.stats.nodes, result.stats.time.as_secs_f64());
+
+    Ok(())
+}
+
+/// Print the terms of the hand-written evaluation of the position in `fen`.
+pub fn print_evaluation(fen: &str, eval_params: &EvalParams) -> Result<(), String> {
+    let position = Position::from_fen_str(fen).map_err(|e| format!("invalid fen {}: {:?}", fen, e))?;
+
+    for line in evaluation_lines(eval_params, &position) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+/// The [`EvalTrace`] of `position`, followed by the network's evaluation if one is loaded, since the bots use that
+/// one instead.
+pub fn evaluation_lines(eval_params: &EvalParams, position: &Position) -> Vec<String> {
+    let mut lines = EvalTrace::new(eval_params, position).lines();
+
+    if let Some(network) = nnue::network() {
+        lines.push(format!("NNUE evaluation: {} for {}", network.evaluate(position), position.current_player().as_str()));
+    }
+
+    lines
+}
@@ -10,7 +10,7 @@ use crate::player::Player;
 use super::{moove::CastleSide, tile_position::TilePosition};
 
 /// Represents a 64-bit bitboard used in the chess engine.
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 
 pub struct Bitboard(pub u64);
 
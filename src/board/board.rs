@@ -7,7 +7,7 @@ use super::{
 };
 use crate::{
     piece::PieceType,
-    pieces::{bishop::Bishop, knight::Knight, pawn::Pawn, queen::Queen, rook::Rook},
+    pieces::{bishop::Bishop, king::King, knight::Knight, pawn::Pawn, queen::Queen, rook::Rook},
     player::Player,
     player_piece::PlayerPiece,
 };
@@ -243,7 +243,7 @@ impl Board {
             PieceType::Rook => &self.rooks,
             PieceType::Knight => &self.knights,
             PieceType::Bishop => &self.bishops,
-            PieceType::Queen => &self.kings,
+            PieceType::Queen => &self.queens,
             PieceType::King => &self.kings,
         }
     }
@@ -356,6 +356,18 @@ impl Board {
         return is_blocking.is_empty() && is_attacked.is_empty();
     }
 
+    /// Tiles attacked by `piece` standing on `tile_pos`, counted the same way as in [`Board::get_attack_mask`].
+    pub fn get_piece_attack_mask(&self, piece: PlayerPiece, tile_pos: TilePosition) -> Bitboard {
+        match piece.piece() {
+            PieceType::Pawn => Bitboard(get_pawn_capture(piece.player(), tile_pos)),
+            PieceType::Rook => Rook::generate_collision_mask(self, piece.player(), tile_pos),
+            PieceType::Knight => Knight::generate_collision_mask(self, piece.player(), tile_pos),
+            PieceType::Bishop => Bishop::generate_collision_mask(self, piece.player(), tile_pos),
+            PieceType::Queen => Queen::generate_collision_mask(self, piece.player(), tile_pos),
+            PieceType::King => King::generate_collision_mask(self, piece.player(), tile_pos),
+        }
+    }
+
     /// Return a player's all possible tiles they can move to as a [`Bitboard`] mask
     pub fn get_attack_mask(&self, player: Player) -> Bitboard {
         let mut player_board = self.get_player_bitboard(player).clone();
@@ -398,24 +410,24 @@ impl Board {
         let mut material = 0;
 
         let pawns = (self.pawns & player_bitboard).0.count_ones();
-        let pawn_phase_value = Pawn::phase_material_value().0 * game_phase.0 + Pawn::phase_material_value().1 + game_phase.1;
+        let pawn_phase_value = Pawn::phase_material_value().0 * game_phase.0 + Pawn::phase_material_value().1 * game_phase.1;
         material += pawns * pawn_phase_value as u32 / 100;
 
         let rooks = (self.rooks & player_bitboard).0.count_ones();
-        let rook_phase_value = Rook::phase_material_value().0 * game_phase.0 + Rook::phase_material_value().1 + game_phase.1;
+        let rook_phase_value = Rook::phase_material_value().0 * game_phase.0 + Rook::phase_material_value().1 * game_phase.1;
         material += rooks * rook_phase_value as u32 / 100;
 
         let bishops = (self.bishops & player_bitboard).0.count_ones();
-        let bishop_phase_value = Bishop::phase_material_value().0 * game_phase.0 + Bishop::phase_material_value().1 + game_phase.1;
+        let bishop_phase_value = Bishop::phase_material_value().0 * game_phase.0 + Bishop::phase_material_value().1 * game_phase.1;
         material += bishops * bishop_phase_value as u32 / 100;
 
 
         let knights = (self.knights & player_bitboard).0.count_ones();
-        let knight_phase_value = Knight::phase_material_value().0 * game_phase.0 + Knight::phase_material_value().1 + game_phase.1;
+        let knight_phase_value = Knight::phase_material_value().0 * game_phase.0 + Knight::phase_material_value().1 * game_phase.1;
         material += knights * knight_phase_value as u32 / 100;
 
         let queens = (self.queens & player_bitboard).0.count_ones();
-        let queen_phase_value = Queen::phase_material_value().0 * game_phase.0 + Queen::phase_material_value().1 + game_phase.1;
+        let queen_phase_value = Queen::phase_material_value().0 * game_phase.0 + Queen::phase_material_value().1 * game_phase.1;
         material += queens * queen_phase_value as u32 / 100;
 
         material as u32
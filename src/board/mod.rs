@@ -8,4 +8,5 @@ pub mod tile_position;
 pub mod moove;
 pub mod game_state;
 pub mod mailbox;
-pub mod zobrist_hash;
\ No newline at end of file
+pub mod zobrist_hash;
+pub mod piece_square_score;
\ No newline at end of file
@@ -1,7 +1,7 @@
 //! Implementations for basic, castling, en passant and promoting moves.
 //! The name is "moove" as `move` is a keyword in Rust.
 
-use crate::{player::Player, player_piece::PlayerPiece};
+use crate::{piece::PieceType, player::Player, player_piece::PlayerPiece};
 
 use super::tile_position::TilePosition;
 
@@ -80,6 +80,21 @@ impl Move {
         }
     }
 
+    /// Get the move in UCI notation, like "e2e4" or "e7e8q". Castling is the king's move.
+    pub fn uci_string(&self) -> String {
+        let promotion = match self {
+            Self::Promoting(promoting_move) => match promoting_move.promotion_piece.piece() {
+                PieceType::Knight => "n",
+                PieceType::Bishop => "b",
+                PieceType::Rook => "r",
+                _ => "q"
+            },
+            _ => ""
+        };
+
+        format!("{}{}{}", self.from_position().notation_string(), self.to_position().notation_string(), promotion).to_lowercase()
+    }
+
     /// Get [`TilePosition`] of the move's starting position.
     pub fn from_position(&self) -> TilePosition {
         match self {
@@ -99,6 +114,58 @@ impl Move {
             Self::Promoting(promoting_move) => promoting_move.to_position(),
         }
     }
+
+    /// Pack the move into 16 bits: 6 bits for the starting tile, 6 bits for the landing tile and 4 bits for the move kind.
+    ///
+    /// A packed value of `0` is never produced for a valid move and can be used to represent "no move".
+    pub fn to_u16(&self) -> u16 {
+        let kind: u16 = match self {
+            Self::Basic(_) => 0,
+            Self::Castling(_) => 1,
+            Self::EnPassant(_) => 2,
+            Self::Promoting(promoting_move) => match promoting_move.promotion_piece().piece() {
+                PieceType::Knight => 3,
+                PieceType::Bishop => 4,
+                PieceType::Rook => 5,
+                _ => 6
+            }
+        };
+
+        (self.from_position().bit_offset() as u16) | (self.to_position().bit_offset() as u16) << 6 | kind << 12
+    }
+
+    /// Unpack a move created with [`Self::to_u16`]. `player` is the [`Player`] making the move.
+    ///
+    /// Returns [`None`] for `0` or an invalid move kind.
+    ///
+    /// [`None`]: Option#variant.None
+    pub fn from_u16(value: u16, player: Player) -> Option<Move> {
+        if value == 0 {
+            return None;
+        }
+
+        let from = TilePosition::from_bit_offset((value & 0x3F) as u32);
+        let to = TilePosition::from_bit_offset((value >> 6 & 0x3F) as u32);
+
+        let promotion_piece = |piece| PlayerPiece::new(player, piece);
+
+        let moove = match value >> 12 {
+            0 => BasicMove::new(from, to).into(),
+            1 => {
+                let side = if to.column() > from.column() { CastleSide::KingSide } else { CastleSide::QueenSide };
+
+                CastlingMove::new(player, side).into()
+            },
+            2 => EnPassantMove::new(from, to, TilePosition::new(to.column(), from.rank())).into(),
+            3 => PromotingMove::new(from, to, promotion_piece(PieceType::Knight)).into(),
+            4 => PromotingMove::new(from, to, promotion_piece(PieceType::Bishop)).into(),
+            5 => PromotingMove::new(from, to, promotion_piece(PieceType::Rook)).into(),
+            6 => PromotingMove::new(from, to, promotion_piece(PieceType::Queen)).into(),
+            _ => return None
+        };
+
+        Some(moove)
+    }
 }
 
 impl From<BasicMove> for Move {
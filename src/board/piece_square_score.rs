@@ -0,0 +1,128 @@
+//! Material and piece-square table scores kept up to date while moves are made, like the Zobrist hash.
+
+use crate::{bot::eval_params::EvalParams, piece::PieceType, player::Player, player_piece::PlayerPiece};
+
+use super::{board::Board, moove::{BasicMove, CastlingMove, EnPassantMove, PromotingMove}, tile_position::TilePosition};
+
+/// Piece values the scores are kept for, the ones of the default [`EvalParams`] and the presets it is built on from
+/// [`EvalParams::PAWN_STRUCTURE`].
+pub static PIECE_VALUES: [(i32, i32); 6] = EvalParams::ENDGAME.piece_values;
+
+/// Piece-square tables the scores are kept for with every table mirrored for Black, see [`PIECE_VALUES`].
+pub static PIECE_SQUARE_TABLES: [[(i32, i32); 64]; 6] = EvalParams::ENDGAME.piece_square_tables;
+
+/// Weight of each piece type in the game phase, indexed like [`PieceType`].
+const PHASE_WEIGHTS: [i32; 6] = [0, 2, 1, 1, 4, 0];
+
+/// Material and piece-square scores of all pieces except the kings, whose table depends on the other pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PieceSquareScore {
+	/// Midgame and endgame score from White's point of view.
+	score: (i32, i32),
+	/// Sum of the phase weights of all pieces, 24 in the starting position.
+	phase: i32
+}
+
+impl PieceSquareScore {
+	pub fn zero() -> Self {
+		Self {
+			score: (0, 0),
+			phase: 0
+		}
+	}
+
+	pub fn from_board(board: &Board) -> Self {
+		let mut s = Self::zero();
+
+		for bit_offset in 0..64 {
+			let tile_position = TilePosition::from_bit_offset(bit_offset);
+
+			if let Some(piece) = board.get_piece(tile_position) {
+				s.add_piece(piece, tile_position);
+			}
+		}
+
+		s
+	}
+
+	/// Midgame and endgame score from White's point of view.
+	pub fn score(&self) -> (i32, i32) {
+		self.score
+	}
+
+	/// Sum of the phase weights of all pieces, see [`calculate_game_phase`][crate::bot::utils::calculate_game_phase].
+	pub fn phase(&self) -> i32 {
+		self.phase
+	}
+
+	pub fn update_basic_move(&mut self, basic_move: BasicMove, moved_piece: PlayerPiece, captured_piece: Option<PlayerPiece>) {
+		self.remove_piece(moved_piece, basic_move.from_position());
+
+		if let Some(p) = captured_piece {
+			self.remove_piece(p, basic_move.to_position());
+		}
+
+		self.add_piece(moved_piece, basic_move.to_position());
+	}
+
+	pub fn update_castling_move(&mut self, castling_move: CastlingMove, king_piece: PlayerPiece) {
+		let rook_piece = PlayerPiece::new(king_piece.player(), PieceType::Rook);
+
+		self.remove_piece(rook_piece, castling_move.rook_from_position());
+		self.add_piece(rook_piece, castling_move.rook_to_position());
+	}
+
+	pub fn update_en_passant_move(&mut self, en_passant_move: EnPassantMove, moved_piece: PlayerPiece, captured_piece: PlayerPiece) {
+		self.remove_piece(moved_piece, en_passant_move.from_position());
+		self.remove_piece(captured_piece, en_passant_move.captured_tile());
+		self.add_piece(moved_piece, en_passant_move.to_position());
+	}
+
+	pub fn update_promoting_move(&mut self, promoting_move: PromotingMove, moved_piece: PlayerPiece, captured_piece: Option<PlayerPiece>) {
+		self.remove_piece(moved_piece, promoting_move.from_position());
+
+		if let Some(p) = captured_piece {
+			self.remove_piece(p, promoting_move.to_position());
+		}
+
+		self.add_piece(promoting_move.promotion_piece(), promoting_move.to_position());
+	}
+
+	fn add_piece(&mut self, piece: PlayerPiece, tile_position: TilePosition) {
+		let (weight, phase) = piece_weight(piece, tile_position);
+
+		self.score.0 += weight.0;
+		self.score.1 += weight.1;
+		self.phase += phase;
+	}
+
+	fn remove_piece(&mut self, piece: PlayerPiece, tile_position: TilePosition) {
+		let (weight, phase) = piece_weight(piece, tile_position);
+
+		self.score.0 -= weight.0;
+		self.score.1 -= weight.1;
+		self.phase -= phase;
+	}
+}
+
+/// Score of `piece` on `tile_position` from White's point of view and its phase weight.
+fn piece_weight(piece: PlayerPiece, tile_position: TilePosition) -> ((i32, i32), i32) {
+	let piece_type = piece.piece() as usize;
+
+	if piece.piece() == PieceType::King {
+		return ((0, 0), 0);
+	}
+
+	let bit_offset = tile_position.bit_offset();
+
+	// Flips the rank for Black
+	let (square, sign) = match piece.player() {
+		Player::White => (bit_offset, 1),
+		Player::Black => (bit_offset ^ 56, -1),
+	};
+
+	let value = PIECE_VALUES[piece_type];
+	let table = PIECE_SQUARE_TABLES[piece_type][square as usize];
+
+	return ((sign * (value.0 + table.0), sign * (value.1 + table.1)), PHASE_WEIGHTS[piece_type]);
+}
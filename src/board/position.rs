@@ -2,12 +2,12 @@
 
 use std::hash::Hash;
 
-use crate::{board::moove::CastleSide, bot::{positioning::get_score_for_piece, utils::calculate_game_phase}, piece::PieceType, pieces::{king::King, pawn::Pawn}, player::Player, player_piece::PlayerPiece};
+use crate::{board::moove::CastleSide, bot::{nnue::{self, Accumulator}, positioning::get_score_for_piece, utils::calculate_game_phase}, piece::PieceType, pieces::{king::King, pawn::Pawn}, player::Player, player_piece::PlayerPiece};
 
-use super::{board::Board, game_state::GameState, moove::{BasicMove, CastlingMove, EnPassantMove, Move, PromotingMove}, move_collision::get_collision_mask, tile_position::TilePosition, zobrist_hash::ZobristHash};
+use super::{board::Board, game_state::GameState, moove::{BasicMove, CastlingMove, EnPassantMove, Move, PromotingMove}, move_collision::get_collision_mask, piece_square_score::PieceSquareScore, tile_position::TilePosition, zobrist_hash::ZobristHash};
 
 /// Represents an entire chess position.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct Position {
     board: Board,
     current_player: Player,
@@ -19,7 +19,13 @@ pub struct Position {
     black_short_castling: bool,
     black_long_castling: bool,
 
+    /// Half moves since the last capture or pawn move, for the fifty-move rule.
+    halfmove_clock: u32,
+
     zobrist_hash: ZobristHash,
+    piece_square_score: PieceSquareScore,
+    /// Only computed while a network is loaded. Kept inline, since the search copies the position for every move.
+    nnue_accumulator: Option<Accumulator>,
 }
 
 impl Hash for Position {
@@ -28,12 +34,33 @@ impl Hash for Position {
     }
 }
 
+/// Positions are equal when the same moves are legal in them. The move counters and the scores kept up to date
+/// with the board aren't compared.
+impl PartialEq for Position {
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board
+            && self.current_player == other.current_player
+            && self.en_passant_target == other.en_passant_target
+            && self.white_short_castling == other.white_short_castling
+            && self.white_long_castling == other.white_long_castling
+            && self.black_short_castling == other.black_short_castling
+            && self.black_long_castling == other.black_long_castling
+    }
+}
+
+impl Eq for Position {}
+
 impl Position {
     /// [`Player`] who's turn it is.
     pub fn current_player(&self) -> Player {
         self.current_player
     }
 
+    /// Half moves since the last capture or pawn move. The game is drawn by the fifty-move rule at 100.
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
     /// Reference to contained [`Board`]
     pub fn board(&self) -> &Board {
         &self.board
@@ -56,7 +83,7 @@ impl Position {
         let player_str = split[1];
         let castling_str = split[2];
         let en_passant_target_str = split[3];
-        let _half_move_clock = split[4];
+        let half_move_clock_str = split[4];
         let _full_move_clock = split[5];
 
         for fen_char in pieces_str.chars() {
@@ -121,6 +148,11 @@ impl Position {
             _ => Some(TilePosition::from_tile_str(en_passant_target_str).unwrap())
         };
 
+        let halfmove_clock = match half_move_clock_str.parse() {
+            Ok(halfmove_clock) => halfmove_clock,
+            Err(_) => return Err(FenParseError::InvalidHalfMoveClock)
+        };
+
         let mut s = Self{
             board,
             current_player,
@@ -129,14 +161,68 @@ impl Position {
             black_short_castling,
             black_long_castling,
             en_passant_target,
+            halfmove_clock,
             ..Default::default()
         };
 
         s.generate_zobrist_hash();
+        s.generate_piece_square_score();
+        s.generate_nnue_accumulator();
 
         Ok(s)
     }
 
+    /// FEN-notation string of the position. The full move number isn't kept, so it is always 1.
+    pub fn to_fen_string(&self) -> String {
+        let mut fen = String::new();
+
+        for rank in (0..8).rev() {
+            let mut empty_tiles = 0;
+
+            for column in 0..8 {
+                match self.get_piece(TilePosition::new(column, rank)) {
+                    Some(piece) => {
+                        if empty_tiles > 0 {
+                            fen.push_str(&empty_tiles.to_string());
+                            empty_tiles = 0;
+                        }
+
+                        fen.push(piece.fen_char());
+                    },
+                    None => empty_tiles += 1,
+                }
+            }
+
+            if empty_tiles > 0 {
+                fen.push_str(&empty_tiles.to_string());
+            }
+
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+
+        fen.push_str(if self.current_player == Player::White { " w " } else { " b " });
+
+        let castling: String = [
+            (self.white_short_castling, 'K'),
+            (self.white_long_castling, 'Q'),
+            (self.black_short_castling, 'k'),
+            (self.black_long_castling, 'q'),
+        ].iter().filter(|(available, _)| *available).map(|(_, c)| *c).collect();
+
+        fen.push_str(if castling.is_empty() { "-" } else { &castling });
+
+        match &self.en_passant_target {
+            Some(tile_pos) => fen.push_str(&format!(" {} ", tile_pos.notation_string().to_ascii_lowercase())),
+            None => fen.push_str(" - "),
+        }
+
+        fen.push_str(&format!("{} 1", self.halfmove_clock));
+
+        return fen;
+    }
+
     /// Print all found legal moves.
     pub fn print_all_legal_moves(&self) {
         let mut counter = 1;
@@ -474,32 +560,66 @@ impl Position {
             self.zobrist_hash.update_en_passant_column(en_passant_tile);
         }
 
+        self.halfmove_clock += 1;
+
         match moove {
             Move::Basic(basic_move) => {
                 let moved_piece = self.get_piece(basic_move.from_position()).unwrap();
                 let captured_piece = self.get_piece(basic_move.to_position());
 
+                if moved_piece.piece() == PieceType::Pawn || captured_piece.is_some() {
+                    self.halfmove_clock = 0;
+                }
+
                 self.zobrist_hash.update_basic_move(basic_move.clone(), moved_piece, captured_piece);
+                self.piece_square_score.update_basic_move(basic_move.clone(), moved_piece, captured_piece);
+
+                if let (Some(network), Some(accumulator)) = (nnue::network(), &mut self.nnue_accumulator) {
+                    accumulator.update_basic_move(network, basic_move.clone(), moved_piece, captured_piece);
+                }
+
                 self.board.move_piece_basic(basic_move);
             },
             Move::Castling(castling_move) => {
                 let moved_piece = self.get_piece(castling_move.from_position()).unwrap();
 
                 self.zobrist_hash.update_castling_move(castling_move.clone(), moved_piece);
+                self.piece_square_score.update_castling_move(castling_move.clone(), moved_piece);
+
+                if let (Some(network), Some(accumulator)) = (nnue::network(), &mut self.nnue_accumulator) {
+                    accumulator.update_castling_move(network, castling_move.clone(), moved_piece);
+                }
+
                 self.board.move_piece_castling(castling_move);
             },
             Move::EnPassant(en_passant_move) => {
                 let moved_piece = self.get_piece(en_passant_move.from_position()).unwrap();
                 let captured_piece = self.get_piece(en_passant_move.captured_tile()).unwrap();
 
+                self.halfmove_clock = 0;
+
                 self.zobrist_hash.update_en_passant_move(en_passant_move.clone(), moved_piece, captured_piece);
+                self.piece_square_score.update_en_passant_move(en_passant_move.clone(), moved_piece, captured_piece);
+
+                if let (Some(network), Some(accumulator)) = (nnue::network(), &mut self.nnue_accumulator) {
+                    accumulator.update_en_passant_move(network, en_passant_move.clone(), moved_piece, captured_piece);
+                }
+
                 self.board.move_piece_en_passant(en_passant_move)
             },
             Move::Promoting(promoting_move) => {
                 let moved_piece = self.get_piece(promoting_move.from_position()).unwrap();
                 let captured_piece = self.get_piece(promoting_move.to_position());
 
+                self.halfmove_clock = 0;
+
                 self.zobrist_hash.update_promoting_move(promoting_move.clone(), moved_piece, captured_piece);
+                self.piece_square_score.update_promoting_move(promoting_move.clone(), moved_piece, captured_piece);
+
+                if let (Some(network), Some(accumulator)) = (nnue::network(), &mut self.nnue_accumulator) {
+                    accumulator.update_promoting_move(network, promoting_move.clone(), moved_piece, captured_piece);
+                }
+
                 self.board.move_piece_promoting(promoting_move);
             },
         }
@@ -507,11 +627,48 @@ impl Position {
         self.current_player = self.current_player.opposite();
     }
 
+    /// Passes the turn to the other player without moving any pieces.
+    ///
+    /// Used by null move pruning. Must not be used while in check.
+    pub fn make_null_move(&mut self) {
+        debug_assert!(!self.is_in_check(self.current_player));
+
+        if let Some(en_passant_tile) = self.en_passant_target.take() {
+            self.zobrist_hash.update_en_passant_column(en_passant_tile);
+        }
+
+        self.zobrist_hash.update_null_move();
+
+        // Repeating a position from before the null move is no real repetition, so repetition checks stop here
+        self.halfmove_clock = 0;
+
+        self.current_player = self.current_player.opposite();
+    }
+
+    /// Return `true` if [`player`][Player] has any pieces other than pawns and the king.
+    pub fn has_non_pawn_material(&self, player: Player) -> bool {
+        let player_board = *self.board.get_player_bitboard(player);
+        let non_pawn_pieces = self.board.rooks | self.board.knights | self.board.bishops | self.board.queens;
+
+        !(player_board & non_pawn_pieces).is_empty()
+    }
+
     /// [`ZobristHash`] for current position.
     pub fn hash(&self) -> &ZobristHash {
         &self.zobrist_hash
     }
 
+    /// Material and piece-square scores for current position.
+    pub fn piece_square_score(&self) -> &PieceSquareScore {
+        &self.piece_square_score
+    }
+
+    /// Hidden layer of the loaded network for current position, see [`nnue`]. `None` if the position was made before
+    /// the network was loaded.
+    pub fn nnue_accumulator(&self) -> Option<&Accumulator> {
+        self.nnue_accumulator.as_ref()
+    }
+
     fn get_en_passant_target_for_move(&self, moove: &Move) -> Option<TilePosition> {
         let from_pos = moove.from_position();
 
@@ -703,26 +860,49 @@ impl Position {
     pub fn generate_zobrist_hash(&mut self) {
         self.zobrist_hash = ZobristHash::from_position(&self);
     }
+
+    pub fn generate_piece_square_score(&mut self) {
+        self.piece_square_score = PieceSquareScore::from_board(&self.board);
+    }
+
+    /// Compute the hidden layer if a network is loaded.
+    pub fn generate_nnue_accumulator(&mut self) {
+        if let Some(network) = nnue::network() {
+            self.nnue_accumulator = Some(Accumulator::new(network, &self.board));
+        }
+    }
 }
 
 impl Position {
     pub fn new(board: Board, current_player: Player) -> Self {
-        Self {
+        let mut s = Self {
             board,
             current_player,
             ..Default::default()
-        }
+        };
+
+        s.generate_zobrist_hash();
+        s.generate_piece_square_score();
+        s.generate_nnue_accumulator();
+
+        s
     }
 
     pub fn empty() -> Self {
-        Self {
+        let mut s = Self {
             board: Board::empty(),
             white_short_castling: false,
             white_long_castling: false,
             black_short_castling: false,
             black_long_castling: false,
             ..Default::default()
-        }
+        };
+
+        s.generate_zobrist_hash();
+        s.generate_piece_square_score();
+        s.generate_nnue_accumulator();
+
+        s
     }
 }
 
@@ -738,10 +918,16 @@ impl Default for Position {
             black_short_castling: true,
             black_long_castling: true,
 
-            zobrist_hash: ZobristHash::zero()
+            halfmove_clock: 0,
+
+            zobrist_hash: ZobristHash::zero(),
+            piece_square_score: PieceSquareScore::zero(),
+            nnue_accumulator: None
         };
 
         s.generate_zobrist_hash();
+        s.generate_piece_square_score();
+        s.generate_nnue_accumulator();
 
         s
     }
@@ -754,4 +940,5 @@ pub enum FenParseError {
     InvalidCastlingChar,
     OutOfBoard,
     UnexpectedEnd,
+    InvalidHalfMoveClock,
 }
\ No newline at end of file
@@ -7,18 +7,22 @@ use super::{moove::{BasicMove, CastleSide, CastlingMove, EnPassantMove, Promotin
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct ZobristHash {
-	value: u64
+	value: u64,
+	/// Hash of only the pawns, used as key of the pawn structure cache.
+	pawn_value: u64
 }
 
 impl ZobristHash {
 	pub fn zero() -> Self {
 		Self {
-			value: 0
+			value: 0,
+			pawn_value: 0
 		}
 	}
 
 	pub fn from_position(position: &Position) -> Self {
 		let mut value = 0;
+		let mut pawn_value = 0;
 
 		for bit_offset in 0..64 {
 			let tile_position = TilePosition::from_bit_offset(bit_offset);
@@ -32,6 +36,7 @@ impl ZobristHash {
 			let piece = piece.unwrap();
 
 			value = value ^ get_zobrist_piece_number(piece, tile_position);
+			pawn_value = pawn_value ^ get_zobrist_pawn_number(piece, tile_position);
 		};
 
 		unsafe {
@@ -61,7 +66,8 @@ impl ZobristHash {
 		};
 
 		Self {
-			value
+			value,
+			pawn_value
 		}
 	}
 
@@ -79,6 +85,12 @@ impl ZobristHash {
 			value = value ^ BLACK_TO_MOVE;
 		}
 
+		self.pawn_value ^= get_zobrist_pawn_number(moved_piece, basic_move.from_position()) ^ get_zobrist_pawn_number(moved_piece, basic_move.to_position());
+
+		if let Some(p) = captured_piece {
+			self.pawn_value ^= get_zobrist_pawn_number(p, basic_move.to_position());
+		}
+
 		self.value = value;
 	}
 
@@ -112,6 +124,10 @@ impl ZobristHash {
 			value = value ^ BLACK_TO_MOVE;
 		}
 
+		self.pawn_value ^= get_zobrist_pawn_number(moved_piece, en_passant_move.from_position());
+		self.pawn_value ^= get_zobrist_pawn_number(captured_piece, en_passant_move.captured_tile());
+		self.pawn_value ^= get_zobrist_pawn_number(moved_piece, en_passant_move.to_position());
+
 		self.value = value;
 	}
 
@@ -130,6 +146,9 @@ impl ZobristHash {
 			value = value ^ BLACK_TO_MOVE;
 		}
 
+		// Captured pieces can't be pawns on the last rank
+		self.pawn_value ^= get_zobrist_pawn_number(moved_piece, promoting_move.from_position());
+
 		self.value = value;
 	}
 
@@ -174,9 +193,20 @@ impl ZobristHash {
 		self.value = value;
 	}
 
+	/// Passes the turn without moving any pieces.
+	pub fn update_null_move(&mut self) {
+		unsafe {
+			self.value = self.value ^ BLACK_TO_MOVE;
+		}
+	}
+
 	pub fn value(&self) -> u64 {
 		self.value
 	}
+
+	pub fn pawn_value(&self) -> u64 {
+		self.pawn_value
+	}
 }
 
 static mut WHITE_PAWN_NUMBERS: [u64; 64] = [0; 64];
@@ -280,4 +310,13 @@ fn get_zobrist_piece_number(piece: PlayerPiece, tile_position: TilePosition) ->
 			}
 		}
 	}
-}
\ No newline at end of file
+}
+
+/// Number of `piece` for the pawn hash, zero for pieces other than pawns.
+fn get_zobrist_pawn_number(piece: PlayerPiece, tile_position: TilePosition) -> u64 {
+	if piece.piece() != PieceType::Pawn {
+		return 0;
+	}
+
+	get_zobrist_piece_number(piece, tile_position)
+}
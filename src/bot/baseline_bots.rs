@@ -0,0 +1,156 @@
+//! Simple bots used as reference opponents when rating the real ones.
+//!
+//! None of them use the time control or can be stopped, the fixed-depth ones always search to their full depth.
+
+use rand::seq::IndexedRandom;
+
+use crate::board::position::Position;
+
+use super::{bot_option::{BotOption, OptionError}, evaluation::Evaluation, evaluation_funcs::{evaluate_material_and_checkmates, evaluate_phase_and_bishop_pair_and_rook_open_column}, score::{Score, MATE}, search_funcs::{alpha_beta_search, negamax_search}, search_limits::SearchLimits, search_observer::SearchObserver, search_result::SearchResult, Bot};
+
+const DEPTH_OPTION: &str = "Depth";
+const MAX_DEPTH: i64 = 8;
+
+/// Plays a uniformly random legal move.
+#[derive(Clone)]
+pub struct RandomBot;
+
+impl Bot for RandomBot {
+	fn search(&self, history: &[Position], _limits: &SearchLimits, _observer: &dyn SearchObserver) -> SearchResult {
+		let position = history.last().expect("history contains the position to search");
+		let legal_moves = position.get_all_legal_moves();
+		let moove = legal_moves.choose(&mut rand::rng()).expect("position has legal moves");
+
+		return SearchResult::from_move(moove.clone(), Score::new(0));
+	}
+
+	fn stop(&self) {}
+}
+
+/// Plays the move winning the most material right away, picking randomly between equal moves.
+#[derive(Clone)]
+pub struct GreedyBot;
+
+impl Bot for GreedyBot {
+	fn search(&self, history: &[Position], _limits: &SearchLimits, _observer: &dyn SearchObserver) -> SearchResult {
+		let position = history.last().expect("history contains the position to search");
+		let player = position.current_player();
+
+		let scored_moves: Vec<_> = position.get_all_legal_moves().into_iter().map(|m| {
+			let mut moved_position = position.clone();
+			moved_position.make_move(m.clone());
+
+			let board = moved_position.board();
+			let material = board.get_material_for_player(player) as i32 - board.get_material_for_player(player.opposite()) as i32;
+
+			(material * 100, m)
+		}).collect();
+
+		let best_score = scored_moves.iter().map(|(score, _)| *score).max().expect("position has legal moves");
+		let best_moves: Vec<_> = scored_moves.into_iter().filter(|(score, _)| *score == best_score).collect();
+		let (score, moove) = best_moves.choose(&mut rand::rng()).unwrap();
+
+		return SearchResult::from_move(moove.clone(), Score::new(*score));
+	}
+
+	fn stop(&self) {}
+}
+
+/// Searches every move to a fixed depth with [`alpha_beta_search`].
+#[derive(Clone)]
+pub struct AlphaBetaBot {
+	depth: u32,
+}
+
+impl AlphaBetaBot {
+	pub fn new(depth: u32) -> Self {
+		Self { depth: depth.max(1) }
+	}
+}
+
+impl Bot for AlphaBetaBot {
+	fn options(&self) -> Vec<BotOption> {
+		vec![BotOption::spin(DEPTH_OPTION, 4, 1, MAX_DEPTH, self.depth as i64)]
+	}
+
+	fn set_option(&mut self, name: &str, value: &str) -> Result<(), OptionError> {
+		match name {
+			DEPTH_OPTION => self.depth = BotOption::parse_spin(name, value, 1, MAX_DEPTH)? as u32,
+			_ => return Err(OptionError::UnknownOption(name.to_owned())),
+		}
+
+		Ok(())
+	}
+
+	fn search(&self, history: &[Position], limits: &SearchLimits, _observer: &dyn SearchObserver) -> SearchResult {
+		let position = history.last().expect("history contains the position to search");
+		let depth = search_depth(self.depth, limits);
+
+		let (eval, moove) = alpha_beta_search(position, evaluate_phase_and_bishop_pair_and_rook_open_column, depth);
+
+		// Mates are scored as -1000000 times one more than the depth left after the mated position
+		let score = if eval.abs() >= MATE {
+			let ply = (depth + 1 - (eval.unsigned_abs() / MATE as u32)) as usize;
+
+			if eval > 0 { Score::mate_in(ply) } else { Score::mated_in(ply) }
+		}
+		else {
+			Score::new(eval)
+		};
+
+		return SearchResult::from_move(moove, score);
+	}
+
+	fn stop(&self) {}
+}
+
+/// Searches every move to a fixed depth with [`negamax_search`], counting only material and mates.
+#[derive(Clone)]
+pub struct NegamaxBot {
+	depth: u32,
+}
+
+impl NegamaxBot {
+	pub fn new(depth: u32) -> Self {
+		Self { depth: depth.max(1) }
+	}
+}
+
+impl Bot for NegamaxBot {
+	fn options(&self) -> Vec<BotOption> {
+		vec![BotOption::spin(DEPTH_OPTION, 3, 1, MAX_DEPTH, self.depth as i64)]
+	}
+
+	fn set_option(&mut self, name: &str, value: &str) -> Result<(), OptionError> {
+		match name {
+			DEPTH_OPTION => self.depth = BotOption::parse_spin(name, value, 1, MAX_DEPTH)? as u32,
+			_ => return Err(OptionError::UnknownOption(name.to_owned())),
+		}
+
+		Ok(())
+	}
+
+	fn search(&self, history: &[Position], limits: &SearchLimits, _observer: &dyn SearchObserver) -> SearchResult {
+		let position = history.last().expect("history contains the position to search");
+		let depth = search_depth(self.depth, limits);
+
+		let (moove, evaluation) = negamax_search(position, evaluate_material_and_checkmates, depth);
+
+		// The distance of a mate isn't known, only that it is within the depth
+		let score = match evaluation {
+			Evaluation::Score(pawns) => Score::new((pawns * 100.0) as i32),
+			Evaluation::Checkmate(true) => Score::mate_in(depth as usize),
+			Evaluation::Checkmate(false) => Score::mated_in(depth as usize),
+			Evaluation::Stalemate | Evaluation::Initial => Score::new(0),
+		};
+
+		return SearchResult::from_move(moove, score);
+	}
+
+	fn stop(&self) {}
+}
+
+/// Depth limit of the search if it is given, otherwise the bot's own depth.
+fn search_depth(depth: u32, limits: &SearchLimits) -> u32 {
+	limits.max_depth().unwrap_or(depth).max(1)
+}
@@ -0,0 +1,101 @@
+//! Settings a [`Bot`][super::Bot] can be configured with, modelled after UCI options.
+
+use std::fmt;
+
+/// Setting of a bot with its current value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotOption {
+	pub name: String,
+	pub kind: OptionKind,
+}
+
+/// Type of a [`BotOption`] with its allowed values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionKind {
+	/// On or off, set with "true" or "false".
+	Check { default: bool, value: bool },
+	/// Whole number from `min` to `max`.
+	Spin { default: i64, min: i64, max: i64, value: i64 },
+	/// One of `choices`.
+	Combo { default: String, choices: Vec<String>, value: String },
+	/// Action without a value, like clearing the transposition table.
+	Button,
+}
+
+impl BotOption {
+	pub fn check(name: &str, default: bool, value: bool) -> Self {
+		Self { name: name.to_owned(), kind: OptionKind::Check { default, value } }
+	}
+
+	pub fn spin(name: &str, default: i64, min: i64, max: i64, value: i64) -> Self {
+		Self { name: name.to_owned(), kind: OptionKind::Spin { default, min, max, value } }
+	}
+
+	pub fn combo(name: &str, default: &str, choices: &[&str], value: &str) -> Self {
+		Self {
+			name: name.to_owned(),
+			kind: OptionKind::Combo {
+				default: default.to_owned(),
+				choices: choices.iter().map(|c| c.to_string()).collect(),
+				value: value.to_owned(),
+			},
+		}
+	}
+
+	pub fn button(name: &str) -> Self {
+		Self { name: name.to_owned(), kind: OptionKind::Button }
+	}
+
+	/// Parse `value` as a check option called `name`.
+	pub fn parse_check(name: &str, value: &str) -> Result<bool, OptionError> {
+		match value {
+			"true" => Ok(true),
+			"false" => Ok(false),
+			_ => Err(OptionError::invalid_value(name, value)),
+		}
+	}
+
+	/// Parse `value` as a spin option called `name` with the range `min..=max`.
+	pub fn parse_spin(name: &str, value: &str, min: i64, max: i64) -> Result<i64, OptionError> {
+		match value.parse::<i64>() {
+			Ok(number) if (min..=max).contains(&number) => Ok(number),
+			_ => Err(OptionError::invalid_value(name, value)),
+		}
+	}
+
+	/// Option as announced in answer to the UCI `uci` command.
+	pub fn uci_string(&self) -> String {
+		match &self.kind {
+			OptionKind::Check { default, .. } => format!("option name {} type check default {}", self.name, default),
+			OptionKind::Spin { default, min, max, .. } => format!("option name {} type spin default {} min {} max {}", self.name, default, min, max),
+			OptionKind::Combo { default, choices, .. } => {
+				let vars: Vec<String> = choices.iter().map(|c| format!("var {}", c)).collect();
+
+				format!("option name {} type combo default {} {}", self.name, default, vars.join(" "))
+			},
+			OptionKind::Button => format!("option name {} type button", self.name),
+		}
+	}
+}
+
+/// Reason an option couldn't be set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionError {
+	UnknownOption(String),
+	InvalidValue { name: String, value: String },
+}
+
+impl OptionError {
+	pub fn invalid_value(name: &str, value: &str) -> Self {
+		Self::InvalidValue { name: name.to_owned(), value: value.to_owned() }
+	}
+}
+
+impl fmt::Display for OptionError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::UnknownOption(name) => write!(f, "unknown option {}", name),
+			Self::InvalidValue { name, value } => write!(f, "invalid value {} for option {}", value, name),
+		}
+	}
+}
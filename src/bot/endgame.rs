@@ -0,0 +1,177 @@
+//! Endgame knowledge: driving a lone king to the edge to mate it, and scaling down the score of endgames which are
+//! hard or impossible to win despite the extra material.
+
+use crate::{board::{bitboard::Bitboard, board::Board, position::Position}, player::Player};
+
+use super::{eval_params::EvalParams, pawn_structure::distance};
+
+/// Scale factor of endgames played out as usual. Scale factors are in 64ths.
+pub const FULL_SCALE: i32 = 64;
+
+const DARK_SQUARES: u64 = 0xAA55_AA55_AA55_AA55;
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = FILE_A << 7;
+
+/// Corners on dark and light squares, where a bishop and knight can force mate.
+const DARK_CORNERS: [u32; 2] = [0, 63];
+const LIGHT_CORNERS: [u32; 2] = [7, 56];
+
+/// Largest number of steps between two squares going along files and ranks.
+const MAX_DISTANCE: i32 = 14;
+
+/// Pieces and pawns of one player.
+struct Material {
+	pawns: u64,
+	knights: u32,
+	bishops: u64,
+	rooks: u32,
+	queens: u32,
+}
+
+impl Material {
+	fn new(board: &Board, player: Player) -> Self {
+		let player_board = *board.get_player_bitboard(player);
+		let count = |pieces: Bitboard| (pieces & player_board).0.count_ones();
+
+		Self {
+			pawns: (board.pawns & player_board).0,
+			knights: count(board.knights),
+			bishops: (board.bishops & player_board).0,
+			rooks: count(board.rooks),
+			queens: count(board.queens),
+		}
+	}
+
+	/// Number of pieces besides the king and pawns.
+	fn pieces(&self) -> u32 {
+		self.knights + self.bishops.count_ones() + self.rooks + self.queens
+	}
+
+	fn is_bare_king(&self) -> bool {
+		self.pawns == 0 && self.pieces() == 0
+	}
+
+	/// Whether the only piece is a single bishop, pawns aside.
+	fn is_lone_bishop(&self) -> bool {
+		self.pieces() == 1 && self.bishops != 0
+	}
+
+	/// Whether the only pieces are a bishop and a knight, without pawns.
+	fn is_bishop_and_knight(&self) -> bool {
+		self.pawns == 0 && self.pieces() == 2 && self.bishops.count_ones() == 1 && self.knights == 1
+	}
+
+	/// Whether the pieces can mate a lone king without promoting a pawn.
+	fn can_mate(&self) -> bool {
+		let bishops = self.bishops.count_ones();
+
+		self.queens > 0 || self.rooks > 0 || bishops >= 2 || (bishops >= 1 && self.knights >= 1)
+	}
+}
+
+/// Bonus of `player` for mating a lone enemy king: the enemy king's distance from the centre and the kings' closeness
+/// to each other. With only a bishop and a knight, which can force mate in just two corners, the enemy king's
+/// closeness to those corners replaces its distance from the centre.
+pub fn mop_up(params: &EvalParams, position: &Position, player: Player) -> (i32, i32) {
+	let board = position.board();
+	let own = Material::new(board, player);
+	let enemy = Material::new(board, player.opposite());
+
+	if !enemy.is_bare_king() || !own.can_mate() {
+		return (0, 0);
+	}
+
+	let own_king = (board.kings & *board.get_player_bitboard(player)).0;
+	let enemy_king = (board.kings & *board.get_player_bitboard(player.opposite())).0;
+
+	// Only in test positions
+	if own_king == 0 || enemy_king == 0 {
+		return (0, 0);
+	}
+
+	let own_king = own_king.trailing_zeros();
+	let enemy_king = enemy_king.trailing_zeros();
+
+	let edge = if own.is_bishop_and_knight() {
+		let corners = if own.bishops & DARK_SQUARES != 0 { DARK_CORNERS } else { LIGHT_CORNERS };
+
+		MAX_DISTANCE - corners.iter().map(|&corner| manhattan_distance(enemy_king, corner)).min().unwrap()
+	}
+	else {
+		centre_distance(enemy_king)
+	};
+
+	let closeness = MAX_DISTANCE - manhattan_distance(own_king, enemy_king);
+
+	return (
+		params.mop_up_edge.0 * edge + params.mop_up_king_distance.0 * closeness,
+		params.mop_up_edge.1 * edge + params.mop_up_king_distance.1 * closeness,
+	);
+}
+
+/// Scale factor of the endgame score of `strong`, the player ahead, for material which rarely or never wins.
+pub fn scale_factor(params: &EvalParams, position: &Position, strong: Player) -> i32 {
+	let board = position.board();
+	let own = Material::new(board, strong);
+	let enemy = Material::new(board, strong.opposite());
+
+	// A knight or bishop alone can't mate
+	if own.pawns == 0 && own.pieces() == 1 && own.knights + own.bishops.count_ones() == 1 {
+		return params.lone_minor_scale;
+	}
+
+	// Two knights can't force mate against a lone king
+	if own.pawns == 0 && own.pieces() == 2 && own.knights == 2 && enemy.is_bare_king() {
+		return params.two_knights_scale;
+	}
+
+	if is_wrong_rook_pawn(board, &own, &enemy, strong) {
+		return params.wrong_rook_pawn_scale;
+	}
+
+	if own.is_lone_bishop() && enemy.is_lone_bishop() && (own.bishops & DARK_SQUARES == 0) != (enemy.bishops & DARK_SQUARES == 0) {
+		return params.opposite_bishops_scale;
+	}
+
+	return FULL_SCALE;
+}
+
+/// Whether `own` has only a bishop and pawns on the a- or h-file, whose promotion square the bishop can't cover,
+/// while the enemy king without pieces holds that square.
+fn is_wrong_rook_pawn(board: &Board, own: &Material, enemy: &Material, strong: Player) -> bool {
+	if !own.is_lone_bishop() || own.pawns == 0 || enemy.pieces() != 0 {
+		return false;
+	}
+
+	let file = if own.pawns & !FILE_A == 0 {
+		0
+	}
+	else if own.pawns & !FILE_H == 0 {
+		7
+	}
+	else {
+		return false;
+	};
+
+	let promotion_square = if strong == Player::White { 56 + file } else { file };
+
+	if (own.bishops & DARK_SQUARES == 0) == (DARK_SQUARES & 1 << promotion_square == 0) {
+		return false;
+	}
+
+	let enemy_king = (board.kings & *board.get_player_bitboard(strong.opposite())).0.trailing_zeros();
+
+	return distance(enemy_king, promotion_square) <= 1;
+}
+
+/// Files plus ranks between a square and the four centre squares.
+fn centre_distance(square: u32) -> i32 {
+	let file = (square % 8) as i32;
+	let rank = (square / 8) as i32;
+
+	return (3 - file).max(file - 4) + (3 - rank).max(rank - 4);
+}
+
+fn manhattan_distance(from: u32, to: u32) -> i32 {
+	return ((from % 8).abs_diff(to % 8) + (from / 8).abs_diff(to / 8)) as i32;
+}
@@ -0,0 +1,586 @@
+//! Weights of the hand-written evaluation, which can be loaded from a file to try tuned values without recompiling.
+//!
+//! Parameter files list a name followed by its values, with the midgame and endgame weight of pairs one after the
+//! other, and `#` starts a comment. Parameters missing from a file keep their default values.
+
+use std::{fmt, fs, io, path::Path};
+
+use crate::{board::{bitboard::Bitboard, piece_square_score::{PIECE_SQUARE_TABLES, PIECE_VALUES}, position::Position, tile_position::TilePosition}, pieces::{bishop::Bishop, knight::Knight, pawn::Pawn, queen::Queen, rook::Rook}, player::Player};
+
+use super::{endgame::{mop_up, scale_factor, FULL_SCALE}, evaluator::Evaluator, king_safety::evaluate_king_safety, pawn_structure::{evaluate_pawn_structure, PawnTable}, piece_activity::evaluate_piece_activity, positioning::{BISHOP_PIECE_SQUARE_TABLE, KING_PIECE_SQUARE_TABLE_CHECKMATE, KNIGHT_PIECE_SQUARE_TABLE, QUEEN_PIECE_SQUARE_TABLE, WHITE_KING_PIECE_SQUARE_TABLE, WHITE_PAWN_PIECE_SQUARE_TABLE, WHITE_ROOK_PIECE_SQUARE_TABLE}, utils::{calculate_game_phase, BISHOP_PAIR_BONUS, OPEN_COLUMN_BONUS}};
+
+/// Names of the piece types in parameter files, in the order of [`PieceType`][crate::piece::PieceType].
+pub const PIECE_NAMES: [&str; 6] = ["pawn", "rook", "knight", "bishop", "queen", "king"];
+
+const PAWN: usize = 0;
+const ROOK: usize = 1;
+const BISHOP: usize = 3;
+const KING: usize = 5;
+
+/// Every weight is a (midgame, endgame) pair, blended by [`calculate_game_phase`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalParams {
+	/// Indexed like [`PIECE_NAMES`]. Kings can't be traded, so their value doesn't matter.
+	pub piece_values: [(i32, i32); 6],
+	/// Indexed like [`PIECE_NAMES`], then by square from White's side of the board. Black's squares are mirrored, see
+	/// [`Self::mirror_all_tables`].
+	pub piece_square_tables: [[(i32, i32); 64]; 6],
+	/// King table used instead while its side has no rooks or queens left.
+	pub checkmate_king_table: [(i32, i32); 64],
+	/// Whether Black's squares are mirrored for the knight, bishop, queen and checkmate king tables too. The evaluation
+	/// functions replaced by the first presets used those tables as they are for both players. Not read from
+	/// parameter files, which always mirror every table.
+	pub mirror_all_tables: bool,
+	pub bishop_pair: (i32, i32),
+	/// Bonus for a rook on a file without pawns.
+	pub rook_open_file: (i32, i32),
+	/// Bonus for a rook on a file with only enemy pawns.
+	pub rook_semi_open_file: (i32, i32),
+	/// Penalty for each pawn with an own pawn in front of it.
+	pub doubled_pawn: (i32, i32),
+	/// Penalty for a pawn without own pawns on the neighbouring files.
+	pub isolated_pawn: (i32, i32),
+	/// Penalty for a pawn which no own pawn can defend and whose way forward is guarded by an enemy pawn.
+	pub backward_pawn: (i32, i32),
+	/// Bonus for a pawn defended by or next to an own pawn, indexed by its rank counted from its own side.
+	pub connected_pawn: [(i32, i32); 8],
+	/// Bonus for a pawn which can become passed by trading off the enemy pawns guarding its way, indexed by rank.
+	pub candidate_passed_pawn: [(i32, i32); 8],
+	pub passed_pawn: [(i32, i32); 8],
+	/// Bonus for a passed pawn with no pieces in front of it, multiplied by the ranks it has advanced.
+	pub passed_pawn_free_path: (i32, i32),
+	/// Weight of the own king's distance to the square in front of a passed pawn, multiplied by the ranks it has advanced.
+	pub passed_pawn_own_king_distance: (i32, i32),
+	/// Like [`Self::passed_pawn_own_king_distance`] for the enemy king.
+	pub passed_pawn_enemy_king_distance: (i32, i32),
+	/// Penalty for having no pawns left, which makes winning harder.
+	pub no_pawns: (i32, i32),
+	/// Bonus for each of the three files around the king by the ranks between the king and the nearest own pawn in
+	/// front of it. Index 0 is used when there is no such pawn close enough.
+	pub king_pawn_shield: [(i32, i32); 4],
+	/// Like [`Self::king_pawn_shield`] for the nearest enemy pawn.
+	pub king_pawn_storm: [(i32, i32); 5],
+	/// Penalty for a file without pawns next to the king.
+	pub king_open_file: (i32, i32),
+	/// Penalty for a file with only enemy pawns next to the king.
+	pub king_semi_open_file: (i32, i32),
+	/// Attack units for each square next to the enemy king a piece attacks, indexed like [`PIECE_NAMES`]. Kings
+	/// don't count.
+	pub king_attack_weight: [i32; 6],
+	/// Weight of the squared attack units, in hundredths.
+	pub king_danger: (i32, i32),
+	/// Bonus for each square a piece attacks that no enemy pawn guards, beyond the usual number for its type. Indexed
+	/// like [`PIECE_NAMES`], pawns and kings don't count.
+	pub mobility: [(i32, i32); 6],
+	/// Bonus for a knight defended by a pawn on the 4th to 6th rank, where no enemy pawn can attack it.
+	pub knight_outpost: (i32, i32),
+	pub bishop_outpost: (i32, i32),
+	/// Bonus for a rook on the 7th rank attacking pawns or cutting off the king.
+	pub rook_on_seventh: (i32, i32),
+	/// Penalty for a rook in the corner shut in by its own king.
+	pub trapped_rook: (i32, i32),
+	/// Penalty for a bishop on a7 or h7 cut off by an enemy pawn on b6 or g6, or one rank lower.
+	pub trapped_bishop: (i32, i32),
+	/// Bonus for each knight by its own pawns above five.
+	pub knight_pawn_count: (i32, i32),
+	/// Like [`Self::knight_pawn_count`] for bishops.
+	pub bishop_pawn_count: (i32, i32),
+	/// Bonus against a lone king for each step it is away from the centre, or closer to the corner the bishop can
+	/// mate in with only a bishop and a knight.
+	pub mop_up_edge: (i32, i32),
+	/// Bonus against a lone king for each step the own king is closer to it.
+	pub mop_up_king_distance: (i32, i32),
+	/// Scale of the endgame score, in 64ths, of the player ahead with opposite-coloured bishops and only pawns besides.
+	pub opposite_bishops_scale: i32,
+	/// Like [`Self::opposite_bishops_scale`] with only two knights against a lone king.
+	pub two_knights_scale: i32,
+	/// Like [`Self::opposite_bishops_scale`] with only a bishop and rook pawns whose promotion square the bishop
+	/// can't cover and the enemy king holds.
+	pub wrong_rook_pawn_scale: i32,
+	/// Like [`Self::opposite_bishops_scale`] with only a knight or bishop and no pawns.
+	pub lone_minor_scale: i32,
+}
+
+impl EvalParams {
+	/// Material counted in whole pawns and the piece-square tables. The pawn and king tables are blended by the game
+	/// phase once for all pieces instead of for each piece, so scores can round differently from the original
+	/// function.
+	pub const MATERIAL_AND_POSITIONING: Self = Self {
+		piece_values: [
+			in_centipawns(Pawn::material_value()),
+			in_centipawns(Rook::material_value()),
+			in_centipawns(Knight::material_value()),
+			in_centipawns(Bishop::material_value()),
+			in_centipawns(Queen::material_value()),
+			(0, 0),
+		],
+		piece_square_tables: [
+			WHITE_PAWN_PIECE_SQUARE_TABLE,
+			untapered(WHITE_ROOK_PIECE_SQUARE_TABLE),
+			untapered(KNIGHT_PIECE_SQUARE_TABLE),
+			untapered(BISHOP_PIECE_SQUARE_TABLE),
+			untapered(QUEEN_PIECE_SQUARE_TABLE),
+			WHITE_KING_PIECE_SQUARE_TABLE,
+		],
+		checkmate_king_table: untapered(KING_PIECE_SQUARE_TABLE_CHECKMATE),
+		mirror_all_tables: false,
+		bishop_pair: (0, 0),
+		rook_open_file: (0, 0),
+		rook_semi_open_file: (0, 0),
+		doubled_pawn: (0, 0),
+		isolated_pawn: (0, 0),
+		backward_pawn: (0, 0),
+		connected_pawn: [(0, 0); 8],
+		candidate_passed_pawn: [(0, 0); 8],
+		passed_pawn: [(0, 0); 8],
+		passed_pawn_free_path: (0, 0),
+		passed_pawn_own_king_distance: (0, 0),
+		passed_pawn_enemy_king_distance: (0, 0),
+		no_pawns: (0, 0),
+		king_pawn_shield: [(0, 0); 4],
+		king_pawn_storm: [(0, 0); 5],
+		king_open_file: (0, 0),
+		king_semi_open_file: (0, 0),
+		king_attack_weight: [0; 6],
+		king_danger: (0, 0),
+		mobility: [(0, 0); 6],
+		knight_outpost: (0, 0),
+		bishop_outpost: (0, 0),
+		rook_on_seventh: (0, 0),
+		trapped_rook: (0, 0),
+		trapped_bishop: (0, 0),
+		knight_pawn_count: (0, 0),
+		bishop_pawn_count: (0, 0),
+		mop_up_edge: (0, 0),
+		mop_up_king_distance: (0, 0),
+		opposite_bishops_scale: FULL_SCALE,
+		two_knights_scale: FULL_SCALE,
+		wrong_rook_pawn_scale: FULL_SCALE,
+		lone_minor_scale: FULL_SCALE,
+	};
+
+	/// Material values depending on the game phase.
+	pub const MATERIAL_AND_POSITIONING_BY_PHASE: Self = Self {
+		piece_values: [
+			Pawn::phase_material_value(),
+			Rook::phase_material_value(),
+			Knight::phase_material_value(),
+			Bishop::phase_material_value(),
+			Queen::phase_material_value(),
+			(0, 0),
+		],
+		..Self::MATERIAL_AND_POSITIONING
+	};
+
+	pub const BY_PHASE_WITH_BISHOP_PAIR: Self = Self {
+		bishop_pair: BISHOP_PAIR_BONUS,
+		..Self::MATERIAL_AND_POSITIONING_BY_PHASE
+	};
+
+	pub const PHASE_AND_BISHOP_PAIR_AND_ROOK_OPEN_COLUMN: Self = Self {
+		rook_open_file: OPEN_COLUMN_BONUS,
+		rook_semi_open_file: (OPEN_COLUMN_BONUS.0 / 2, OPEN_COLUMN_BONUS.1 / 2),
+		..Self::BY_PHASE_WITH_BISHOP_PAIR
+	};
+
+	/// Adds doubled, isolated, backward, connected and passed pawns, and mirrors every table for Black.
+	pub const PAWN_STRUCTURE: Self = Self {
+		mirror_all_tables: true,
+		doubled_pawn: (-10, -25),
+		isolated_pawn: (-10, -15),
+		backward_pawn: (-8, -12),
+		connected_pawn: [(0, 0), (3, 2), (5, 4), (8, 7), (14, 15), (25, 30), (45, 60), (0, 0)],
+		candidate_passed_pawn: [(0, 0), (0, 3), (2, 5), (4, 10), (8, 18), (12, 30), (0, 0), (0, 0)],
+		passed_pawn: [(0, 0), (2, 8), (4, 12), (8, 20), (15, 35), (25, 60), (40, 90), (0, 0)],
+		passed_pawn_free_path: (0, 6),
+		passed_pawn_own_king_distance: (0, -2),
+		passed_pawn_enemy_king_distance: (0, 3),
+		no_pawns: (-50, -50),
+		..Self::PHASE_AND_BISHOP_PAIR_AND_ROOK_OPEN_COLUMN
+	};
+
+	/// Adds king safety, which only counts in the midgame.
+	pub const KING_SAFETY: Self = Self {
+		king_pawn_shield: [(-20, 0), (12, 0), (6, 0), (2, 0)],
+		king_pawn_storm: [(0, 0), (-4, 0), (-25, 0), (-12, 0), (-5, 0)],
+		king_open_file: (-25, 0),
+		king_semi_open_file: (-12, 0),
+		king_attack_weight: [1, 3, 2, 2, 5, 0],
+		king_danger: (-40, 0),
+		..Self::PAWN_STRUCTURE
+	};
+
+	/// Adds mobility and the other piece activity terms.
+	pub const PIECE_ACTIVITY: Self = Self {
+		mobility: [(0, 0), (2, 4), (4, 4), (4, 5), (1, 2), (0, 0)],
+		knight_outpost: (20, 12),
+		bishop_outpost: (10, 5),
+		rook_on_seventh: (10, 25),
+		trapped_rook: (-40, -10),
+		trapped_bishop: (-100, -80),
+		knight_pawn_count: (3, 3),
+		bishop_pawn_count: (-3, -3),
+		..Self::KING_SAFETY
+	};
+
+	/// The default parameters, adding mop-up against a lone king and scaling down drawish endgames.
+	pub const ENDGAME: Self = Self {
+		mop_up_edge: (10, 10),
+		mop_up_king_distance: (4, 4),
+		opposite_bishops_scale: 32,
+		two_knights_scale: 0,
+		wrong_rook_pawn_scale: 0,
+		lone_minor_scale: 0,
+		..Self::PIECE_ACTIVITY
+	};
+
+	/// Read parameters from the file at `path`.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, EvalParamsError> {
+		let text = fs::read_to_string(path).map_err(EvalParamsError::Io)?;
+
+		Self::parse(&text)
+	}
+
+	/// Write every parameter to the file at `path`, in the format [`EvalParams::load`] reads.
+	pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		fs::write(path, self.to_string())
+	}
+
+	/// Parse the contents of a parameter file.
+	pub fn parse(text: &str) -> Result<Self, EvalParamsError> {
+		let mut entries: Vec<(&str, Vec<i32>)> = Vec::new();
+
+		for token in text.lines().flat_map(|line| line.split('#').next().unwrap_or("").split_whitespace()) {
+			if token.starts_with(|c: char| c.is_ascii_alphabetic()) {
+				entries.push((token, Vec::new()));
+				continue;
+			}
+
+			let value = token.parse().map_err(|_| EvalParamsError::InvalidNumber(token.to_owned()))?;
+
+			match entries.last_mut() {
+				Some((_, values)) => values.push(value),
+				None => return Err(EvalParamsError::MissingName(token.to_owned())),
+			}
+		}
+
+		let mut params = Self::default();
+		let mut fields = params.fields_mut();
+
+		for (name, values) in entries {
+			let (_, field) = fields.iter_mut().find(|(field_name, _)| field_name == name)
+				.ok_or_else(|| EvalParamsError::UnknownParameter(name.to_owned()))?;
+
+			if field.len() != values.len() {
+				return Err(EvalParamsError::WrongValueCount { name: name.to_owned(), expected: field.len(), found: values.len() });
+			}
+
+			for (target, value) in field.iter_mut().zip(values) {
+				**target = value;
+			}
+		}
+
+		drop(fields);
+
+		Ok(params)
+	}
+
+	/// Every parameter with its name in files and its values, with the midgame and endgame weight of pairs alternating.
+	pub fn fields_mut(&mut self) -> Vec<(String, Vec<&mut i32>)> {
+		let mut fields = Vec::new();
+
+		for ((name, value), table) in PIECE_NAMES.iter().zip(&mut self.piece_values).zip(&mut self.piece_square_tables) {
+			fields.push((format!("{}_value", name), pair_values(value)));
+			fields.push((format!("{}_table", name), table.iter_mut().flat_map(pair_values).collect()));
+		}
+
+		fields.push(("checkmate_king_table".to_owned(), self.checkmate_king_table.iter_mut().flat_map(pair_values).collect()));
+		fields.push(("bishop_pair".to_owned(), pair_values(&mut self.bishop_pair)));
+		fields.push(("rook_open_file".to_owned(), pair_values(&mut self.rook_open_file)));
+		fields.push(("rook_semi_open_file".to_owned(), pair_values(&mut self.rook_semi_open_file)));
+		fields.push(("doubled_pawn".to_owned(), pair_values(&mut self.doubled_pawn)));
+		fields.push(("isolated_pawn".to_owned(), pair_values(&mut self.isolated_pawn)));
+		fields.push(("backward_pawn".to_owned(), pair_values(&mut self.backward_pawn)));
+		fields.push(("connected_pawn".to_owned(), self.connected_pawn.iter_mut().flat_map(pair_values).collect()));
+		fields.push(("candidate_passed_pawn".to_owned(), self.candidate_passed_pawn.iter_mut().flat_map(pair_values).collect()));
+		fields.push(("passed_pawn".to_owned(), self.passed_pawn.iter_mut().flat_map(pair_values).collect()));
+		fields.push(("passed_pawn_free_path".to_owned(), pair_values(&mut self.passed_pawn_free_path)));
+		fields.push(("passed_pawn_own_king_distance".to_owned(), pair_values(&mut self.passed_pawn_own_king_distance)));
+		fields.push(("passed_pawn_enemy_king_distance".to_owned(), pair_values(&mut self.passed_pawn_enemy_king_distance)));
+		fields.push(("no_pawns".to_owned(), pair_values(&mut self.no_pawns)));
+		fields.push(("king_pawn_shield".to_owned(), self.king_pawn_shield.iter_mut().flat_map(pair_values).collect()));
+		fields.push(("king_pawn_storm".to_owned(), self.king_pawn_storm.iter_mut().flat_map(pair_values).collect()));
+		fields.push(("king_open_file".to_owned(), pair_values(&mut self.king_open_file)));
+		fields.push(("king_semi_open_file".to_owned(), pair_values(&mut self.king_semi_open_file)));
+		fields.push(("king_attack_weight".to_owned(), self.king_attack_weight.iter_mut().collect()));
+		fields.push(("king_danger".to_owned(), pair_values(&mut self.king_danger)));
+		fields.push(("mobility".to_owned(), self.mobility.iter_mut().flat_map(pair_values).collect()));
+		fields.push(("knight_outpost".to_owned(), pair_values(&mut self.knight_outpost)));
+		fields.push(("bishop_outpost".to_owned(), pair_values(&mut self.bishop_outpost)));
+		fields.push(("rook_on_seventh".to_owned(), pair_values(&mut self.rook_on_seventh)));
+		fields.push(("trapped_rook".to_owned(), pair_values(&mut self.trapped_rook)));
+		fields.push(("trapped_bishop".to_owned(), pair_values(&mut self.trapped_bishop)));
+		fields.push(("knight_pawn_count".to_owned(), pair_values(&mut self.knight_pawn_count)));
+		fields.push(("bishop_pawn_count".to_owned(), pair_values(&mut self.bishop_pawn_count)));
+		fields.push(("mop_up_edge".to_owned(), pair_values(&mut self.mop_up_edge)));
+		fields.push(("mop_up_king_distance".to_owned(), pair_values(&mut self.mop_up_king_distance)));
+		fields.push(("opposite_bishops_scale".to_owned(), vec![&mut self.opposite_bishops_scale]));
+		fields.push(("two_knights_scale".to_owned(), vec![&mut self.two_knights_scale]));
+		fields.push(("wrong_rook_pawn_scale".to_owned(), vec![&mut self.wrong_rook_pawn_scale]));
+		fields.push(("lone_minor_scale".to_owned(), vec![&mut self.lone_minor_scale]));
+
+		fields
+	}
+
+	/// Score of `position` in centipawns from the point of view of the player to move, looking up the pawn structure
+	/// in `pawn_table` if it is given. With `position_scores` the material and piece-square scores are taken from
+	/// [`Position::piece_square_score`], which is only right if [`Self::uses_position_scores`].
+	pub fn evaluate_with_pawn_table(&self, position: &Position, pawn_table: Option<&PawnTable>, position_scores: bool) -> i32 {
+		let game_phase = calculate_game_phase(position);
+		let (midgame, endgame) = self.white_score(position, pawn_table, position_scores);
+		let endgame = endgame * self.endgame_scale(position, endgame) / FULL_SCALE;
+
+		let score = (midgame * game_phase.0 + endgame * game_phase.1) / 100;
+
+		if position.current_player() == Player::White { score } else { -score }
+	}
+
+	/// Scale factor in 64ths of `endgame`, the endgame score of `position` from White's point of view, for material
+	/// which is hard to win with.
+	pub fn endgame_scale(&self, position: &Position, endgame: i32) -> i32 {
+		if !self.has_scale_factors() {
+			return FULL_SCALE;
+		}
+
+		let strong = if endgame >= 0 { Player::White } else { Player::Black };
+
+		scale_factor(self, position, strong)
+	}
+
+	/// Whether the piece values and tables are the ones [`Position`] keeps scores for, so they don't have to be
+	/// summed up for every evaluation.
+	pub fn uses_position_scores(&self) -> bool {
+		self.piece_values == PIECE_VALUES && self.piece_square_tables == PIECE_SQUARE_TABLES && self.mirror_all_tables
+	}
+
+	/// Midgame and endgame score of `position` from White's point of view.
+	fn white_score(&self, position: &Position, pawn_table: Option<&PawnTable>, position_scores: bool) -> (i32, i32) {
+		// Everything but the kings, whose table depends on the other pieces
+		let mut score = if position_scores { position.piece_square_score().score() } else { (0, 0) };
+
+		for (player, sign) in [(Player::White, 1), (Player::Black, -1)] {
+			let terms = self.piece_terms(position, player, position_scores);
+
+			for weight in [terms.material, terms.placement, terms.rook_files, terms.bishop_pair] {
+				score.0 += sign * weight.0;
+				score.1 += sign * weight.1;
+			}
+		}
+
+		if self.has_pawn_structure() {
+			let pawn_score = evaluate_pawn_structure(self, position, pawn_table);
+
+			score.0 += pawn_score.0;
+			score.1 += pawn_score.1;
+		}
+
+		if self.has_king_safety() {
+			let king_score = evaluate_king_safety(self, position);
+
+			score.0 += king_score.0;
+			score.1 += king_score.1;
+		}
+
+		if self.has_piece_activity() {
+			let activity_score = evaluate_piece_activity(self, position);
+
+			score.0 += activity_score.0;
+			score.1 += activity_score.1;
+		}
+
+		if self.has_mop_up() {
+			let white = mop_up(self, position, Player::White);
+			let black = mop_up(self, position, Player::Black);
+
+			score.0 += white.0 - black.0;
+			score.1 += white.1 - black.1;
+		}
+
+		score
+	}
+
+	/// Material, piece-square, rook file and bishop pair scores of `player`'s pieces. With `position_scores` the
+	/// material and tables of all pieces but the king are left out, since [`Position::piece_square_score`] has them.
+	pub fn piece_terms(&self, position: &Position, player: Player, position_scores: bool) -> PieceTerms {
+		let board = position.board();
+		let piece_boards = [board.pawns, board.rooks, board.knights, board.bishops, board.queens, board.kings];
+		let player_board = *board.get_player_bitboard(player);
+		let has_heavy_pieces = player_board & (board.rooks | board.queens) != 0;
+
+		let mut terms = PieceTerms::default();
+
+		let add = |term: &mut (i32, i32), weight: (i32, i32)| {
+			term.0 += weight.0;
+			term.1 += weight.1;
+		};
+
+		for (piece, piece_board) in piece_boards.iter().enumerate() {
+			if position_scores && piece != KING && piece != ROOK {
+				continue;
+			}
+
+			let checkmate_king = piece == KING && !has_heavy_pieces;
+			let table = if checkmate_king { &self.checkmate_king_table } else { &self.piece_square_tables[piece] };
+			// Only the pawn, rook and king tables had mirrored copies for Black in the original evaluation
+			let mirrored = self.mirror_all_tables || (piece == PAWN || piece == ROOK || piece == KING) && !checkmate_king;
+			let mut pieces = *piece_board & player_board;
+
+			while pieces != 0 {
+				let bit_offset = pieces.pop_lsb();
+				// Flips the rank for Black
+				let square = if player == Player::White || !mirrored { bit_offset } else { bit_offset ^ 56 };
+
+				if !position_scores || piece == KING {
+					add(&mut terms.material, self.piece_values[piece]);
+					add(&mut terms.placement, table[square as usize]);
+				}
+
+				if piece == ROOK {
+					let file_mask = Bitboard::generate_column_mask(TilePosition::from_bit_offset(bit_offset).column());
+
+					if file_mask & board.pawns == 0 {
+						add(&mut terms.rook_files, self.rook_open_file);
+					}
+					else if file_mask & board.pawns & player_board == 0 {
+						add(&mut terms.rook_files, self.rook_semi_open_file);
+					}
+				}
+			}
+		}
+
+		if (player_board & piece_boards[BISHOP]).0.count_ones() >= 2 {
+			terms.bishop_pair = self.bishop_pair;
+		}
+
+		terms
+	}
+
+	/// Return `false` for the presets which don't evaluate the pawn structure, to skip it.
+	fn has_pawn_structure(&self) -> bool {
+		self.doubled_pawn != (0, 0) || self.isolated_pawn != (0, 0) || self.backward_pawn != (0, 0)
+			|| self.connected_pawn != [(0, 0); 8] || self.candidate_passed_pawn != [(0, 0); 8] || self.passed_pawn != [(0, 0); 8]
+			|| self.passed_pawn_free_path != (0, 0) || self.passed_pawn_own_king_distance != (0, 0)
+			|| self.passed_pawn_enemy_king_distance != (0, 0) || self.no_pawns != (0, 0)
+	}
+
+	/// Return `false` for the presets which don't evaluate king safety, to skip it.
+	fn has_king_safety(&self) -> bool {
+		self.king_pawn_shield != [(0, 0); 4] || self.king_pawn_storm != [(0, 0); 5] || self.king_open_file != (0, 0)
+			|| self.king_semi_open_file != (0, 0) || self.king_danger != (0, 0)
+	}
+
+	/// Return `false` for the presets which don't evaluate piece activity, to skip it.
+	fn has_piece_activity(&self) -> bool {
+		self.mobility != [(0, 0); 6] || self.knight_outpost != (0, 0) || self.bishop_outpost != (0, 0)
+			|| self.rook_on_seventh != (0, 0) || self.trapped_rook != (0, 0) || self.trapped_bishop != (0, 0)
+			|| self.knight_pawn_count != (0, 0) || self.bishop_pawn_count != (0, 0)
+	}
+
+	/// Return `false` for the presets without mop-up, to skip it.
+	fn has_mop_up(&self) -> bool {
+		self.mop_up_edge != (0, 0) || self.mop_up_king_distance != (0, 0)
+	}
+
+	/// Return `false` for the presets which don't scale drawish endgames, to skip looking for them.
+	fn has_scale_factors(&self) -> bool {
+		self.opposite_bishops_scale != FULL_SCALE || self.two_knights_scale != FULL_SCALE
+			|| self.wrong_rook_pawn_scale != FULL_SCALE || self.lone_minor_scale != FULL_SCALE
+	}
+}
+
+impl Default for EvalParams {
+	fn default() -> Self {
+		Self::ENDGAME
+	}
+}
+
+/// Evaluates without caching the pawn structure, see [`ClassicalEvaluator`][super::evaluator::ClassicalEvaluator].
+impl Evaluator for EvalParams {
+	fn evaluate(&self, position: &Position) -> i32 {
+		self.evaluate_with_pawn_table(position, None, self.uses_position_scores())
+	}
+}
+
+/// Writes the file format read by [`EvalParams::parse`], with a line per rank of the tables.
+impl fmt::Display for EvalParams {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (name, values) in self.clone().fields_mut() {
+			write!(f, "{}", name)?;
+
+			for (i, value) in values.iter().enumerate() {
+				if values.len() > 2 && i % 16 == 0 {
+					write!(f, "\n   ")?;
+				}
+
+				write!(f, " {:>4}", value)?;
+			}
+
+			writeln!(f)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Scores of one player's pieces, see [`EvalParams::piece_terms`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PieceTerms {
+	pub material: (i32, i32),
+	pub placement: (i32, i32),
+	pub rook_files: (i32, i32),
+	pub bishop_pair: (i32, i32),
+}
+
+/// Reason a parameter file couldn't be loaded.
+#[derive(Debug)]
+pub enum EvalParamsError {
+	Io(io::Error),
+	UnknownParameter(String),
+	InvalidNumber(String),
+	/// A value before the first parameter name.
+	MissingName(String),
+	WrongValueCount { name: String, expected: usize, found: usize },
+}
+
+impl fmt::Display for EvalParamsError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Io(error) => write!(f, "couldn't read parameters: {}", error),
+			Self::UnknownParameter(name) => write!(f, "unknown parameter {}", name),
+			Self::InvalidNumber(token) => write!(f, "invalid number {}", token),
+			Self::MissingName(token) => write!(f, "value {} doesn't follow a parameter name", token),
+			Self::WrongValueCount { name, expected, found } => write!(f, "parameter {} needs {} values, found {}", name, expected, found),
+		}
+	}
+}
+
+fn pair_values((midgame, endgame): &mut (i32, i32)) -> Vec<&mut i32> {
+	vec![midgame, endgame]
+}
+
+const fn in_centipawns(pawns: u32) -> (i32, i32) {
+	(pawns as i32 * 100, pawns as i32 * 100)
+}
+
+/// Table with the same weights in every game phase.
+const fn untapered(table: [i32; 64]) -> [(i32, i32); 64] {
+	let mut tapered = [(0, 0); 64];
+	let mut i = 0;
+
+	while i < 64 {
+		tapered[i] = (table[i], table[i]);
+		i += 1;
+	}
+
+	tapered
+}
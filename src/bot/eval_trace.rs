@@ -0,0 +1,119 @@
+//! Breakdown of the hand-written evaluation into its terms, to see why the bots like or dislike a position.
+
+use std::fmt;
+
+use crate::{board::position::Position, player::Player};
+
+use super::{endgame::{mop_up, FULL_SCALE}, eval_params::EvalParams, king_safety::king_safety, pawn_structure::side_pawn_structure, piece_activity::piece_activity, utils::calculate_game_phase};
+
+/// Midgame and endgame score of one term for each player, higher is better for that player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceTerm {
+	pub name: &'static str,
+	pub white: (i32, i32),
+	pub black: (i32, i32),
+}
+
+impl TraceTerm {
+	/// Midgame and endgame score from White's point of view.
+	pub fn difference(&self) -> (i32, i32) {
+		(self.white.0 - self.black.0, self.white.1 - self.black.1)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalTrace {
+	pub terms: Vec<TraceTerm>,
+	/// Midgame and endgame percentage the scores are blended with.
+	pub phase: (i32, i32),
+	/// Scale of the endgame scores in 64ths, see [`EvalParams::endgame_scale`].
+	pub scale: i32,
+	pub current_player: Player,
+}
+
+impl EvalTrace {
+	/// Trace the evaluation of `position` with `params`.
+	pub fn new(params: &EvalParams, position: &Position) -> Self {
+		let side_terms = [Player::White, Player::Black].map(|player| {
+			let pieces = params.piece_terms(position, player, false);
+			let (mobility, activity) = piece_activity(params, position, player);
+
+			[
+				pieces.material,
+				pieces.placement,
+				pieces.bishop_pair,
+				pieces.rook_files,
+				side_pawn_structure(params, position, player),
+				king_safety(params, position, player),
+				mobility,
+				activity,
+				mop_up(params, position, player),
+			]
+		});
+
+		let names = ["Material", "Piece-square", "Bishop pair", "Rook files", "Pawn structure", "King safety", "Mobility", "Piece activity", "Mop-up"];
+
+		let terms = names.iter().enumerate().map(|(i, &name)| TraceTerm {
+			name,
+			white: side_terms[0][i],
+			black: side_terms[1][i],
+		}).collect::<Vec<_>>();
+
+		let endgame = terms.iter().map(|t| t.difference().1).sum();
+
+		Self {
+			scale: params.endgame_scale(position, endgame),
+			terms,
+			phase: calculate_game_phase(position),
+			current_player: position.current_player(),
+		}
+	}
+
+	/// Blend a midgame and endgame score by the game phase, after scaling the endgame score.
+	pub fn tapered(&self, score: (i32, i32)) -> i32 {
+		(score.0 * self.phase.0 + score.1 * self.scale / FULL_SCALE * self.phase.1) / 100
+	}
+
+	/// Midgame and endgame score of all terms from White's point of view.
+	pub fn white_score(&self) -> (i32, i32) {
+		self.terms.iter().map(|t| t.difference()).fold((0, 0), |sum, d| (sum.0 + d.0, sum.1 + d.1))
+	}
+
+	/// The evaluation, from the point of view of the player to move like [`Evaluator`][super::evaluator::Evaluator].
+	pub fn total(&self) -> i32 {
+		let score = self.tapered(self.white_score());
+
+		if self.current_player == Player::White { score } else { -score }
+	}
+
+	/// A table row for every term and the total, for printing or drawing line by line.
+	pub fn lines(&self) -> Vec<String> {
+		let mut lines = vec![
+			format!("{:<15}{:>13}{:>13}{:>13}{:>8}", "Term", "White mg eg", "Black mg eg", "Total mg eg", "Blended"),
+		];
+
+		let row = |name: &str, white: String, black: String, total: (i32, i32)| {
+			format!("{:<15}{:>13}{:>13}{:>13}{:>8}", name, white, black, pair(total), self.tapered(total))
+		};
+
+		for term in &self.terms {
+			lines.push(row(term.name, pair(term.white), pair(term.black), term.difference()));
+		}
+
+		lines.push(row("Total", String::new(), String::new(), self.white_score()));
+		lines.push(format!("Phase: {}% midgame, {}% endgame, endgame scaled by {}/{}", self.phase.0, self.phase.1, self.scale, FULL_SCALE));
+		lines.push(format!("Evaluation: {} for {}, totals are from White's side", self.total(), self.current_player.as_str()));
+
+		lines
+	}
+}
+
+impl fmt::Display for EvalTrace {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.lines().join("\n"))
+	}
+}
+
+fn pair((midgame, endgame): (i32, i32)) -> String {
+	format!("{} {}", midgame, endgame)
+}
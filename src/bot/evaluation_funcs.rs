@@ -2,7 +2,13 @@
 
 use crate::board::{game_state::GameState, position::Position};
 
-use super::{evaluation::Evaluation, utils::{bishop_pair_bonus, calculate_game_phase, rook_open_column_bonus}};
+use super::{eval_params::EvalParams, evaluation::Evaluation, evaluator::Evaluator, piece_activity::mobility};
+
+// Presets of the functions using [`EvalParams`], kept in statics so evaluating doesn't copy them.
+static MATERIAL_AND_POSITIONING: EvalParams = EvalParams::MATERIAL_AND_POSITIONING;
+static MATERIAL_AND_POSITIONING_BY_PHASE: EvalParams = EvalParams::MATERIAL_AND_POSITIONING_BY_PHASE;
+static BY_PHASE_WITH_BISHOP_PAIR: EvalParams = EvalParams::BY_PHASE_WITH_BISHOP_PAIR;
+static PHASE_AND_BISHOP_PAIR_AND_ROOK_OPEN_COLUMN: EvalParams = EvalParams::PHASE_AND_BISHOP_PAIR_AND_ROOK_OPEN_COLUMN;
 
 pub fn evaluate_material_only(position: &Position) -> Evaluation {
 	let own_material = position.board().get_material_for_player(position.current_player()) as f32;
@@ -43,18 +49,13 @@ pub fn evaluate_material_and_mobility_i32(position: &Position) -> i32 {
 	let own_material = position.board().get_material_for_player(position.current_player()) as i32;
 	let opponent_material = position.board().get_material_for_player(position.current_player().opposite()) as i32;
 
-	let mobility_score = position.get_all_legal_moves().len() as i32;
+	let mobility_score = mobility(position, position.current_player()) - mobility(position, position.current_player().opposite());
 
 	return (own_material - opponent_material) * 100 + mobility_score;
 }
 
 pub fn evaluate_material_and_positioning(position: &Position) -> i32 {
-	let own_material = position.board().get_material_for_player(position.current_player()) as i32;
-	let opponent_material = position.board().get_material_for_player(position.current_player().opposite()) as i32;
-
-	let positioning_score = position.get_positioning_score_for_player(position.current_player()) - position.get_positioning_score_for_player(position.current_player().opposite());
-
-	return (own_material - opponent_material) * 100 + positioning_score;
+	return MATERIAL_AND_POSITIONING.evaluate(position);
 }
 
 pub fn evaluate_material_and_positioning_debug(position: &Position) -> (i32, i32, i32) {
@@ -67,38 +68,13 @@ pub fn evaluate_material_and_positioning_debug(position: &Position) -> (i32, i32
 }
 
 pub fn evaluate_material_and_positioning_by_phase(position: &Position) -> i32 {
-	let game_phase = calculate_game_phase(position);
-
-	let own_material = position.board().get_phase_material_for_player(position.current_player(), game_phase) as i32;
-	let opponent_material = position.board().get_phase_material_for_player(position.current_player().opposite(), game_phase) as i32;
-
-	let positioning_score = position.get_positioning_score_for_player_by_phase(position.current_player(), game_phase) - position.get_positioning_score_for_player_by_phase(position.current_player().opposite(), game_phase);
-
-	return (own_material - opponent_material) + positioning_score;
+	return MATERIAL_AND_POSITIONING_BY_PHASE.evaluate(position);
 }
 
 pub fn evaluate_material_and_positioning_by_phase_with_bishop_pair(position: &Position) -> i32 {
-	let game_phase = calculate_game_phase(position);
-
-	let own_material = position.board().get_phase_material_for_player(position.current_player(), game_phase) as i32;
-	let opponent_material = position.board().get_phase_material_for_player(position.current_player().opposite(), game_phase) as i32;
-
-	let positioning_score = position.get_positioning_score_for_player_by_phase(position.current_player(), game_phase) - position.get_positioning_score_for_player_by_phase(position.current_player().opposite(), game_phase);
-	let bishop_pair_bonus = bishop_pair_bonus(position, game_phase);
-
-	return (own_material - opponent_material) + positioning_score + bishop_pair_bonus;
+	return BY_PHASE_WITH_BISHOP_PAIR.evaluate(position);
 }
 
 pub fn evaluate_phase_and_bishop_pair_and_rook_open_column(position: &Position) -> i32 {
-	let game_phase = calculate_game_phase(position);
-
-	let own_material = position.board().get_phase_material_for_player(position.current_player(), game_phase) as i32;
-	let opponent_material = position.board().get_phase_material_for_player(position.current_player().opposite(), game_phase) as i32;
-
-	let positioning_score = position.get_positioning_score_for_player_by_phase(position.current_player(), game_phase) - position.get_positioning_score_for_player_by_phase(position.current_player().opposite(), game_phase);
-	
-	let rook_open_column_bonus = rook_open_column_bonus(position, game_phase);
-	let bishop_pair_bonus = bishop_pair_bonus(position, game_phase);
-
-	return (own_material - opponent_material) + positioning_score + bishop_pair_bonus + rook_open_column_bonus;
-}
\ No newline at end of file
+	return PHASE_AND_BISHOP_PAIR_AND_ROOK_OPEN_COLUMN.evaluate(position);
+}
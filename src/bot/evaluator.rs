@@ -0,0 +1,49 @@
+//! Static evaluation used by the searches.
+
+use std::sync::Arc;
+
+use crate::board::position::Position;
+
+use super::{eval_params::EvalParams, pawn_structure::PawnTable};
+
+/// Scores positions for a search. Shared between search threads, so it can't keep mutable state without locking.
+pub trait Evaluator: Send + Sync {
+	/// Score of `position` in centipawns from the point of view of the player to move.
+	fn evaluate(&self, position: &Position) -> i32;
+}
+
+/// Plain evaluation functions, like the ones in [`super::evaluation_funcs`].
+impl<F: Fn(&Position) -> i32 + Send + Sync> Evaluator for F {
+	fn evaluate(&self, position: &Position) -> i32 {
+		self(position)
+	}
+}
+
+/// Hand-written evaluation with [`EvalParams`], caching the pawn structure. Clones share the cache.
+#[derive(Clone)]
+pub struct ClassicalEvaluator {
+	params: EvalParams,
+	pawn_table: Arc<PawnTable>,
+	/// Checked once, see [`EvalParams::uses_position_scores`].
+	position_scores: bool,
+}
+
+impl ClassicalEvaluator {
+	pub fn new(params: EvalParams) -> Self {
+		Self {
+			position_scores: params.uses_position_scores(),
+			params,
+			pawn_table: Arc::new(PawnTable::new()),
+		}
+	}
+
+	pub fn params(&self) -> &EvalParams {
+		&self.params
+	}
+}
+
+impl Evaluator for ClassicalEvaluator {
+	fn evaluate(&self, position: &Position) -> i32 {
+		self.params.evaluate_with_pawn_table(position, Some(&self.pawn_table), self.position_scores)
+	}
+}
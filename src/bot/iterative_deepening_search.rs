@@ -1,50 +1,165 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::{atomic::{AtomicBool, Ordering}, Arc}, time::Duration};
 
 use rand::seq::IndexedRandom;
 
 use crate::{board::{moove::Move, position::Position}, opening_book::load_opening_book};
 
-use super::{search_funcs::{iterative_deepening, iterative_deepening_no_ext}, transposition_table::TranspositionTable, Bot};
+use super::{bot_option::{BotOption, OptionError}, evaluator::Evaluator, score::Score, search_funcs::{iterative_deepening, iterative_deepening_no_ext, last_capture_square}, search_limits::SearchLimits, search_observer::{SearchEvent, SearchObserver}, search_options::SearchOptions, search_result::SearchResult, time_manager::TimeManager, transposition_table::{TranspositionTable, DEFAULT_SIZE_MB}, Bot};
 
-#[derive(Clone)]
+/// Search time of [`iterative_deepening_no_ext`] when the limits don't have a time control.
+const NO_EXTENSIONS_DEFAULT_TIME: Duration = Duration::from_secs(10);
+
+// Names and ranges of the options for [`Bot::set_option`]
+const HASH_OPTION: &str = "Hash";
+const CLEAR_HASH_OPTION: &str = "Clear Hash";
+pub const OWN_BOOK_OPTION: &str = "OwnBook";
+pub const MULTI_PV_OPTION: &str = "MultiPV";
+const CONTEMPT_OPTION: &str = "Contempt";
+
+const MAX_HASH_MB: i64 = 65536;
+const MAX_MULTI_PV: i64 = 256;
+const MAX_CONTEMPT: i64 = 1000;
+
+/// Clones share the transposition table, but each has its own stop signal so that a clone running a search can be
+/// stopped on its own.
 pub struct IterativeDeepeningSearch {
 	transposition_table: Arc<TranspositionTable>,
-	evaluation_fn: fn(&Position) -> i32,
+	evaluator: Arc<dyn Evaluator>,
 	opening_book: HashMap<u64, Vec<Move>>,
+	use_book: bool,
 	use_extensions: bool,
+	search_options: SearchOptions,
+	stop: Arc<AtomicBool>,
 }
 
 impl IterativeDeepeningSearch {
-	pub fn new(evaluation_fn: fn(&Position) -> i32, use_extensions: bool) -> Self {
+	pub fn new(evaluator: impl Evaluator + 'static, use_extensions: bool) -> Self {
+		Self::with_options(evaluator, use_extensions, SearchOptions::default())
+	}
+
+	/// Create a bot with specific [`SearchOptions`]. Options only apply when `use_extensions` is `true`.
+	pub fn with_options(evaluator: impl Evaluator + 'static, use_extensions: bool, search_options: SearchOptions) -> Self {
+		Self::with_table(evaluator, use_extensions, search_options, TranspositionTable::new())
+	}
+
+	/// Like [`Self::with_options`] with a transposition table of about `megabytes`, cheaper to create than the default
+	/// one when running many bots at once.
+	pub fn with_hash_size(evaluator: impl Evaluator + 'static, use_extensions: bool, search_options: SearchOptions, megabytes: usize) -> Self {
+		Self::with_table(evaluator, use_extensions, search_options, TranspositionTable::with_size_mb(megabytes))
+	}
+
+	fn with_table(evaluator: impl Evaluator + 'static, use_extensions: bool, search_options: SearchOptions, transposition_table: TranspositionTable) -> Self {
 		Self {
-			transposition_table: Arc::new(TranspositionTable::new()),
-			evaluation_fn,
+			transposition_table: Arc::new(transposition_table),
+			evaluator: Arc::new(evaluator),
 			opening_book: load_opening_book(),
-			use_extensions
+			use_book: true,
+			use_extensions,
+			search_options,
+			stop: Arc::new(AtomicBool::new(false)),
+		}
+	}
+
+	pub fn search_options(&self) -> &SearchOptions {
+		&self.search_options
+	}
+
+	pub fn set_search_options(&mut self, search_options: SearchOptions) {
+		self.search_options = search_options;
+	}
+
+	/// Random move of the opening book for `position` if the book is used, reported to `observer`.
+	pub fn book_move(&self, position: &Position, observer: &dyn SearchObserver) -> Option<SearchResult> {
+		if !self.use_book {
+			return None;
+		}
+
+		let m = self.opening_book.get(&position.hash().value())?.choose(&mut rand::rng())?;
+
+		observer.notify(SearchEvent::BookMove(m.clone()));
+
+		Some(SearchResult::from_move(m.clone(), Score::new(0)))
+	}
+}
+
+impl Clone for IterativeDeepeningSearch {
+	fn clone(&self) -> Self {
+		Self {
+			transposition_table: self.transposition_table.clone(),
+			evaluator: self.evaluator.clone(),
+			opening_book: self.opening_book.clone(),
+			use_book: self.use_book,
+			use_extensions: self.use_extensions,
+			search_options: self.search_options.clone(),
+			stop: Arc::new(AtomicBool::new(false)),
 		}
 	}
 }
 
 impl Bot for IterativeDeepeningSearch {
-	fn search_best_move(&self, position: &Position, search_time: Duration) -> (i32, Move) {
-		if let Some(next_moves) = self.opening_book.get(&position.hash().value()) {
-			let m_opt = next_moves.choose(&mut rand::rng());
+	fn new_game(&mut self) {
+		self.transposition_table.clear();
+	}
 
-			for m in next_moves {
-				println!("B: {}", m.debug_string());
-			}
-			
-			if let Some(m) = m_opt {
-				println!("Book move: {}", m.debug_string());
-				return (0, m.clone());
+	fn options(&self) -> Vec<BotOption> {
+		let default_options = SearchOptions::default();
+
+		vec![
+			BotOption::spin(HASH_OPTION, DEFAULT_SIZE_MB as i64, 1, MAX_HASH_MB, self.transposition_table.size_mb() as i64),
+			BotOption::button(CLEAR_HASH_OPTION),
+			BotOption::check(OWN_BOOK_OPTION, true, self.use_book),
+			BotOption::spin(MULTI_PV_OPTION, default_options.multi_pv as i64, 1, MAX_MULTI_PV, self.search_options.multi_pv as i64),
+			BotOption::spin(CONTEMPT_OPTION, default_options.contempt as i64, -MAX_CONTEMPT, MAX_CONTEMPT, self.search_options.contempt as i64),
+		]
+	}
+
+	fn set_option(&mut self, name: &str, value: &str) -> Result<(), OptionError> {
+		match name {
+			HASH_OPTION => {
+				let megabytes = BotOption::parse_spin(name, value, 1, MAX_HASH_MB)?;
+
+				self.transposition_table = Arc::new(TranspositionTable::with_size_mb(megabytes as usize));
+			},
+			CLEAR_HASH_OPTION => self.transposition_table.clear(),
+			OWN_BOOK_OPTION => self.use_book = BotOption::parse_check(name, value)?,
+			MULTI_PV_OPTION => self.search_options.multi_pv = BotOption::parse_spin(name, value, 1, MAX_MULTI_PV)? as usize,
+			CONTEMPT_OPTION => self.search_options.contempt = BotOption::parse_spin(name, value, -MAX_CONTEMPT, MAX_CONTEMPT)? as i32,
+			_ => return Err(OptionError::UnknownOption(name.to_owned())),
+		}
+
+		Ok(())
+	}
+
+	fn search(&self, history: &[Position], limits: &SearchLimits, observer: &dyn SearchObserver) -> SearchResult {
+		let (position, previous_positions) = history.split_last().expect("history contains the position to search");
+
+		// Analysing several lines needs a search, even in book positions
+		if self.search_options.multi_pv <= 1 {
+			if let Some(result) = self.book_move(position, observer) {
+				return result;
 			}
 		}
 
-		if self.use_extensions {
-			iterative_deepening(position, self.evaluation_fn, search_time, self.transposition_table.clone())
+		let result = if self.use_extensions {
+			let previous_hashes: Vec<u64> = previous_positions.iter().map(|p| p.hash().value()).collect();
+
+			iterative_deepening(position, &previous_hashes, last_capture_square(history), self.evaluator.clone(), limits, self.stop.clone(), observer, self.transposition_table.clone(), &self.search_options)
+				.expect("position to search has legal moves")
 		}
 		else {
-			iterative_deepening_no_ext(position, self.evaluation_fn, search_time, self.transposition_table.clone())
-		}
+			// The search without extensions only supports a time limit, can't be stopped and doesn't detect repetitions
+			let search_time = limits.active_time_control().map_or(NO_EXTENSIONS_DEFAULT_TIME, |t| TimeManager::new(t).soft_limit());
+
+			iterative_deepening_no_ext(position, self.evaluator.clone(), search_time, observer, self.transposition_table.clone())
+		};
+
+		// Ready for the next search
+		self.stop.store(false, Ordering::Relaxed);
+
+		result
 	}
-}
\ No newline at end of file
+
+	fn stop(&self) {
+		self.stop.store(true, Ordering::Relaxed);
+	}
+}
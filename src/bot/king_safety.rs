@@ -0,0 +1,138 @@
+//! King safety evaluation: the pawns in front of the king, open files next to it and enemy attacks on it.
+
+use crate::{board::{bitboard::Bitboard, position::Position, tile_position::TilePosition}, piece::PieceType, pieces::king::King, player::Player, player_piece::PlayerPiece};
+
+use super::{eval_params::EvalParams, pawn_structure::{file_mask, ranks_above}};
+
+/// Attack units above which the danger stops growing.
+const MAX_ATTACK_UNITS: i32 = 40;
+
+const ATTACKING_PIECES: [PieceType; 5] = [PieceType::Pawn, PieceType::Rook, PieceType::Knight, PieceType::Bishop, PieceType::Queen];
+
+/// Score of both kings' safety from White's point of view.
+pub fn evaluate_king_safety(params: &EvalParams, position: &Position) -> (i32, i32) {
+	let white = king_safety(params, position, Player::White);
+	let black = king_safety(params, position, Player::Black);
+
+	return (white.0 - black.0, white.1 - black.1);
+}
+
+/// Safety of `player`'s king, higher is safer.
+pub fn king_safety(params: &EvalParams, position: &Position, player: Player) -> (i32, i32) {
+	let board = position.board();
+	let king = board.kings & *board.get_player_bitboard(player);
+
+	if king == 0 {
+		return (0, 0);
+	}
+
+	let king_square = king.0.trailing_zeros();
+
+	let mut score = king_shelter(params, position, player, king_square);
+
+	if params.king_danger != (0, 0) {
+		let units = attack_units(params, position, player, king_square).min(MAX_ATTACK_UNITS);
+
+		// Grows with the square of the attack, a single attacker is rarely dangerous
+		score.0 += params.king_danger.0 * units * units / 100;
+		score.1 += params.king_danger.1 * units * units / 100;
+	}
+
+	return score;
+}
+
+/// Pawn shield, pawn storm and open file terms of the three files around `player`'s king.
+fn king_shelter(params: &EvalParams, position: &Position, player: Player, king_square: u32) -> (i32, i32) {
+	let board = position.board();
+	let own_pawns = (board.pawns & *board.get_player_bitboard(player)).0;
+	let enemy_pawns = (board.pawns & *board.get_player_bitboard(player.opposite())).0;
+
+	// Looked at from White's side, so that the pawns in front of the king are above it
+	let (own_pawns, enemy_pawns, king_square) = match player {
+		Player::White => (own_pawns, enemy_pawns, king_square),
+		Player::Black => (own_pawns.swap_bytes(), enemy_pawns.swap_bytes(), king_square ^ 56),
+	};
+
+	let king_rank = king_square / 8;
+	// A king on the edge is sheltered by the same files as a king next to it
+	let center_file = (king_square % 8).clamp(1, 6);
+
+	let mut score = (0, 0);
+
+	let mut add = |weight: (i32, i32)| {
+		score.0 += weight.0;
+		score.1 += weight.1;
+	};
+
+	for file in center_file - 1..=center_file + 1 {
+		let file = file_mask(file);
+		let in_front = file & ranks_above(king_rank);
+
+		add(params.king_pawn_shield[ranks_to_nearest(own_pawns & in_front, king_rank, params.king_pawn_shield.len())]);
+		add(params.king_pawn_storm[ranks_to_nearest(enemy_pawns & in_front, king_rank, params.king_pawn_storm.len())]);
+
+		if own_pawns & file == 0 {
+			add(if enemy_pawns & file == 0 { params.king_open_file } else { params.king_semi_open_file });
+		}
+	}
+
+	return score;
+}
+
+/// Ranks between `rank` and the lowest of `pawns`, or 0 if there is none closer than `limit` ranks.
+fn ranks_to_nearest(pawns: u64, rank: u32, limit: usize) -> usize {
+	if pawns == 0 {
+		return 0;
+	}
+
+	let distance = (pawns.trailing_zeros() / 8 - rank) as usize;
+
+	if distance < limit { distance } else { 0 }
+}
+
+/// Attack units of the enemy pieces attacking the squares around `player`'s king, weighted by
+/// [`EvalParams::king_attack_weight`]. Zero unless at least two pieces take part in the attack.
+fn attack_units(params: &EvalParams, position: &Position, player: Player, king_square: u32) -> i32 {
+	let board = position.board();
+	let enemy = player.opposite();
+	let enemy_board = *board.get_player_bitboard(enemy);
+
+	let king_tile = TilePosition::from_bit_offset(king_square);
+	let ring = King::get_movement_mask(king_tile).0 | 1 << king_square;
+
+	// The ring and the squares in front of it
+	let zone = Bitboard(match player {
+		Player::White => ring | ring << 8,
+		Player::Black => ring | ring >> 8,
+	});
+
+	let mut attackers = 0;
+	let mut units = 0;
+
+	for piece_type in ATTACKING_PIECES {
+		let weight = params.king_attack_weight[piece_type as usize];
+
+		if weight == 0 {
+			continue;
+		}
+
+		let piece = PlayerPiece::new(enemy, piece_type);
+		let mut pieces = *board.get_piece_bitboard(piece_type) & enemy_board;
+
+		while pieces != 0 {
+			let bit_offset = pieces.pop_lsb();
+			let attacks = board.get_piece_attack_mask(piece, TilePosition::from_bit_offset(bit_offset)) & zone;
+
+			if attacks != 0 {
+				attackers += 1;
+				units += weight * attacks.0.count_ones() as i32;
+			}
+		}
+	}
+
+	if attackers < 2 {
+		return 0;
+	}
+
+	return units;
+}
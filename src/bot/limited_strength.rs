@@ -0,0 +1,242 @@
+//! Bot playing below full strength, for casual games.
+
+use std::cell::RefCell;
+
+use rand::seq::IndexedRandom;
+
+use crate::board::position::Position;
+
+use super::{bot_option::{BotOption, OptionError, OptionKind}, iterative_deepening_search::{IterativeDeepeningSearch, MULTI_PV_OPTION}, search_limits::SearchLimits, search_observer::{SearchEvent, SearchInfo, SearchObserver}, search_result::SearchResult, Bot};
+
+pub const SKILL_LEVEL_OPTION: &str = "Skill Level";
+pub const LIMIT_STRENGTH_OPTION: &str = "UCI_LimitStrength";
+const ELO_OPTION: &str = "UCI_Elo";
+
+pub const MAX_SKILL_LEVEL: u32 = 20;
+/// Elo of skill level 0. Every skill level adds [`ELO_PER_SKILL_LEVEL`].
+const MIN_ELO: u32 = 800;
+const ELO_PER_SKILL_LEVEL: u32 = 100;
+const MAX_ELO: u32 = MIN_ELO + MAX_SKILL_LEVEL * ELO_PER_SKILL_LEVEL;
+const DEFAULT_ELO: u32 = 1500;
+
+/// Lines searched by a weakened bot to pick its move from.
+const CANDIDATE_LINES: usize = 4;
+/// Nodes searched at skill level 0. Every two skill levels double them.
+const MIN_NODES: u64 = 1000;
+/// Centipawns of evaluation noise and of the move choice temperature per skill level below the maximum.
+const CENTIPAWNS_PER_WEAKNESS: i32 = 10;
+
+/// How strong a [`LimitedStrength`] bot plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strength {
+	/// Level from 0 to [`MAX_SKILL_LEVEL`], which is full strength.
+	Skill(u32),
+	/// Rough Elo rating to play at, from 800 to 2800.
+	Elo(u32),
+}
+
+impl Strength {
+	pub fn skill_level(&self) -> u32 {
+		match self {
+			Self::Skill(level) => (*level).min(MAX_SKILL_LEVEL),
+			Self::Elo(elo) => (elo.clamp(&MIN_ELO, &MAX_ELO) - MIN_ELO) / ELO_PER_SKILL_LEVEL,
+		}
+	}
+}
+
+/// Wrapper around [`IterativeDeepeningSearch`] which searches less deep, evaluates with noise and sometimes plays one of
+/// the next best moves, more often the closer its score is to the best one.
+#[derive(Clone)]
+pub struct LimitedStrength {
+	bot: IterativeDeepeningSearch,
+	skill_level: u32,
+	/// Play at [`Self::elo`] instead of [`Self::skill_level`].
+	limit_strength: bool,
+	elo: u32,
+	/// Lines asked for with the MultiPV option, shown when at full strength.
+	multi_pv: usize,
+}
+
+impl LimitedStrength {
+	pub fn new(bot: IterativeDeepeningSearch, strength: Strength) -> Self {
+		let mut limited = Self {
+			multi_pv: bot.search_options().multi_pv,
+			bot,
+			skill_level: MAX_SKILL_LEVEL,
+			limit_strength: false,
+			elo: DEFAULT_ELO,
+		};
+
+		limited.set_strength(strength);
+
+		limited
+	}
+
+	pub fn set_strength(&mut self, strength: Strength) {
+		match strength {
+			Strength::Skill(level) => {
+				self.skill_level = level.min(MAX_SKILL_LEVEL);
+				self.limit_strength = false;
+			},
+			Strength::Elo(elo) => {
+				self.elo = elo.clamp(MIN_ELO, MAX_ELO);
+				self.limit_strength = true;
+			},
+		}
+
+		self.apply_strength();
+	}
+
+	pub fn strength(&self) -> Strength {
+		if self.limit_strength {
+			return Strength::Elo(self.elo);
+		}
+
+		Strength::Skill(self.skill_level)
+	}
+
+	/// Skill levels below the maximum, 0 at full strength.
+	fn weakness(&self) -> u32 {
+		MAX_SKILL_LEVEL - self.strength().skill_level()
+	}
+
+	/// Configure the wrapped bot's search for the current strength.
+	fn apply_strength(&mut self) {
+		let weakness = self.weakness() as i32;
+		let mut search_options = self.bot.search_options().clone();
+
+		search_options.eval_noise = weakness * CENTIPAWNS_PER_WEAKNESS;
+		search_options.multi_pv = if weakness > 0 { self.multi_pv.max(CANDIDATE_LINES) } else { self.multi_pv };
+
+		self.bot.set_search_options(search_options);
+	}
+
+	/// `limits` tightened to the depth and nodes of the current strength.
+	fn strength_limits(&self, limits: &SearchLimits) -> SearchLimits {
+		let mut limits = limits.clone();
+
+		if self.weakness() == 0 {
+			return limits;
+		}
+
+		let skill_level = self.strength().skill_level();
+		let depth = 1 + skill_level / 2;
+		let nodes = MIN_NODES << (skill_level / 2);
+
+		limits.depth = Some(limits.depth.map_or(depth, |d| d.min(depth)));
+		limits.nodes = Some(limits.nodes.map_or(nodes, |n| n.min(nodes)));
+
+		limits
+	}
+
+	/// Pick one of `lines` at random, weighting each by how close its score is to the best line's.
+	fn choose_line<'a>(&self, lines: &'a [SearchInfo]) -> Option<&'a SearchInfo> {
+		let temperature = (self.weakness() as i32 * CENTIPAWNS_PER_WEAKNESS) as f64;
+		let best_score = lines.first()?.score.value();
+
+		lines.choose_weighted(&mut rand::rng(), |line| {
+			let loss = (best_score - line.score.value()) as f64;
+
+			(-loss / temperature).exp()
+		}).ok()
+	}
+}
+
+impl Bot for LimitedStrength {
+	fn new_game(&mut self) {
+		self.bot.new_game();
+	}
+
+	fn options(&self) -> Vec<BotOption> {
+		let mut options = self.bot.options();
+
+		// The wrapped bot searches more lines than asked for when weakened
+		for option in &mut options {
+			if let (MULTI_PV_OPTION, OptionKind::Spin { value, .. }) = (option.name.as_str(), &mut option.kind) {
+				*value = self.multi_pv as i64;
+			}
+		}
+
+		options.push(BotOption::spin(SKILL_LEVEL_OPTION, MAX_SKILL_LEVEL as i64, 0, MAX_SKILL_LEVEL as i64, self.skill_level as i64));
+		options.push(BotOption::check(LIMIT_STRENGTH_OPTION, false, self.limit_strength));
+		options.push(BotOption::spin(ELO_OPTION, DEFAULT_ELO as i64, MIN_ELO as i64, MAX_ELO as i64, self.elo as i64));
+
+		options
+	}
+
+	fn set_option(&mut self, name: &str, value: &str) -> Result<(), OptionError> {
+		match name {
+			MULTI_PV_OPTION => {
+				// Checks the value
+				self.bot.set_option(name, value)?;
+				self.multi_pv = self.bot.search_options().multi_pv;
+			},
+			SKILL_LEVEL_OPTION => self.skill_level = BotOption::parse_spin(name, value, 0, MAX_SKILL_LEVEL as i64)? as u32,
+			LIMIT_STRENGTH_OPTION => self.limit_strength = BotOption::parse_check(name, value)?,
+			ELO_OPTION => self.elo = BotOption::parse_spin(name, value, MIN_ELO as i64, MAX_ELO as i64)? as u32,
+			_ => return self.bot.set_option(name, value),
+		}
+
+		self.apply_strength();
+
+		Ok(())
+	}
+
+	fn search(&self, history: &[Position], limits: &SearchLimits, observer: &dyn SearchObserver) -> SearchResult {
+		if self.weakness() == 0 {
+			return self.bot.search(history, limits, observer);
+		}
+
+		// The wrapped bot skips the book for the extra lines it searches, only the lines asked for count here
+		if self.multi_pv <= 1 {
+			if let Some(result) = self.bot.book_move(history.last().expect("history contains the position to search"), observer) {
+				return result;
+			}
+		}
+
+		let recorder = LineRecorder { observer, lines: RefCell::new(Vec::new()) };
+		let result = self.bot.search(history, &self.strength_limits(limits), &recorder);
+		let lines = recorder.lines.into_inner();
+
+		// Lines from an unfinished iteration are incomplete
+		if lines.first().and_then(|line| line.pv.first()) != Some(&result.best_move) {
+			return result;
+		}
+
+		match self.choose_line(&lines) {
+			Some(line) => SearchResult {
+				best_move: line.pv[0].clone(),
+				score: line.score,
+				pv: line.pv.clone(),
+				stats: result.stats,
+			},
+			None => result,
+		}
+	}
+
+	fn stop(&self) {
+		self.bot.stop();
+	}
+}
+
+/// Forwards search events while keeping the lines of the latest iteration.
+struct LineRecorder<'a> {
+	observer: &'a dyn SearchObserver,
+	lines: RefCell<Vec<SearchInfo>>,
+}
+
+impl SearchObserver for LineRecorder<'_> {
+	fn notify(&self, event: SearchEvent) {
+		if let SearchEvent::Iteration(info) = &event {
+			let mut lines = self.lines.borrow_mut();
+
+			if info.multi_pv == 1 {
+				lines.clear();
+			}
+
+			lines.push(info.clone());
+		}
+
+		self.observer.notify(event);
+	}
+}
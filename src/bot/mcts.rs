@@ -0,0 +1,568 @@
+//! Monte Carlo tree search with PUCT selection, as an alternative to the alpha-beta search.
+//!
+//! Leaves are valued by the best evaluation one move ahead, or by short random rollouts, squashed to a win
+//! probability. Several threads share the tree behind a lock, spreading out over it with virtual losses. Once the tree
+//! reaches its size limit, leaves are still evaluated but no longer expanded.
+
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex}, thread, time::{Duration, Instant}};
+
+use rand::seq::IndexedRandom;
+
+use crate::board::{moove::Move, position::Position};
+
+use super::{bot_option::{BotOption, OptionError}, evaluator::Evaluator, score::Score, search_limits::SearchLimits, search_observer::{SearchEvent, SearchInfo, SearchObserver}, search_result::{SearchResult, SearchStats}, time_manager::TimeManager, Bot};
+
+// Names and ranges of the options for [`Bot::set_option`]. Fractional options are set in hundredths.
+const EXPLORATION_OPTION: &str = "Exploration";
+const FPU_REDUCTION_OPTION: &str = "FPU Reduction";
+const VIRTUAL_LOSS_OPTION: &str = "Virtual Loss";
+const THREADS_OPTION: &str = "Threads";
+const ROLLOUT_DEPTH_OPTION: &str = "Rollout Depth";
+const REUSE_TREE_OPTION: &str = "Reuse Tree";
+/// In thousands of nodes.
+const TREE_SIZE_OPTION: &str = "Tree Size";
+
+const DEFAULT_EXPLORATION: i64 = 150;
+const DEFAULT_FPU_REDUCTION: i64 = 20;
+const DEFAULT_VIRTUAL_LOSS: i64 = 3;
+const MAX_THREADS: i64 = 64;
+const MAX_ROLLOUT_DEPTH: i64 = 64;
+/// About 200 MB of nodes.
+const DEFAULT_TREE_SIZE: i64 = 2000;
+const MAX_TREE_SIZE: i64 = 100_000;
+
+/// Centipawns at which the win probability is 10 to 1.
+const WIN_PROBABILITY_SCALE: f64 = 400.0;
+/// Centipawns by which a move has to be worse than the best one for its prior to drop by a factor of e.
+const PRIOR_TEMPERATURE: f64 = 100.0;
+/// Time between progress reports, which are also when the depth limit is checked.
+const REPORT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Clones share the tree kept for the next search, but each has its own stop signal.
+pub struct MctsBot {
+	evaluator: Arc<dyn Evaluator>,
+	/// Weight of the prior and visit count based exploration term.
+	exploration: f64,
+	/// How much lower than its parent an unvisited move is valued.
+	fpu_reduction: f64,
+	/// Losses counted for each thread currently searching below a node.
+	virtual_loss: u32,
+	threads: usize,
+	/// Random moves played from a leaf before evaluating it. 0 evaluates the leaf directly.
+	rollout_depth: u32,
+	reuse_tree: bool,
+	/// Nodes the tree can grow to, so that long searches don't run out of memory.
+	max_tree_nodes: usize,
+	tree: Arc<Mutex<Option<Tree>>>,
+	stop: Arc<AtomicBool>,
+}
+
+impl MctsBot {
+	pub fn new(evaluator: impl Evaluator + 'static) -> Self {
+		Self {
+			evaluator: Arc::new(evaluator),
+			exploration: DEFAULT_EXPLORATION as f64 / 100.0,
+			fpu_reduction: DEFAULT_FPU_REDUCTION as f64 / 100.0,
+			virtual_loss: DEFAULT_VIRTUAL_LOSS as u32,
+			threads: 1,
+			rollout_depth: 0,
+			reuse_tree: true,
+			max_tree_nodes: DEFAULT_TREE_SIZE as usize * 1000,
+			tree: Arc::new(Mutex::new(None)),
+			stop: Arc::new(AtomicBool::new(false)),
+		}
+	}
+
+	/// Select a leaf, evaluate it and back its value up to the root.
+	fn playout(&self, tree: &Mutex<Tree>, history: &[u64]) {
+		let (path, position, leaf_value) = {
+			let mut tree = tree.lock().unwrap();
+
+			let mut path = vec![0];
+			let mut position = tree.root_position.clone();
+			let mut path_hashes = vec![position.hash().value()];
+
+			let leaf_value = loop {
+				let node = &tree.nodes[*path.last().unwrap()];
+
+				if node.terminal.is_some() || node.children.is_empty() {
+					break node.terminal;
+				}
+
+				let child = tree.select_child(*path.last().unwrap(), self.exploration, self.fpu_reduction);
+
+				tree.nodes[child].virtual_loss += self.virtual_loss;
+				position.make_move(tree.nodes[child].moove.clone().unwrap());
+				path.push(child);
+
+				// Repetitions depend on the path, so the node isn't marked as terminal
+				let hash = position.hash().value();
+
+				if history.contains(&hash) || path_hashes.contains(&hash) {
+					break Some(0.5);
+				}
+
+				path_hashes.push(hash);
+			};
+
+			tree.max_depth = tree.max_depth.max(path.len() - 1);
+
+			(path, position, leaf_value)
+		};
+
+		let (value, expansion) = match leaf_value {
+			Some(value) => (value, None),
+			None => self.evaluate_leaf(&position),
+		};
+
+		let mut tree = tree.lock().unwrap();
+		let leaf = *path.last().unwrap();
+
+		// Another thread may have expanded the leaf in the meantime
+		if tree.nodes[leaf].children.is_empty() && tree.nodes[leaf].terminal.is_none() {
+			match expansion {
+				Some(children) if tree.nodes.len() + children.len() <= self.max_tree_nodes => tree.expand(leaf, children),
+				// The tree is full, the leaf is evaluated again next time
+				Some(_) => {},
+				None if leaf_value.is_none() => tree.nodes[leaf].terminal = Some(value),
+				None => {},
+			}
+		}
+
+		tree.backpropagate(&path, value, self.virtual_loss);
+	}
+
+	/// Win probability of the player to move in `position` with the moves and their priors to expand it with,
+	/// [`None`] if the game is over.
+	fn evaluate_leaf(&self, position: &Position) -> (f64, Option<Vec<(Move, f64)>>) {
+		let legal_moves = position.get_all_legal_moves();
+
+		if legal_moves.is_empty() {
+			let value = if position.is_in_check(position.current_player()) { 0.0 } else { 0.5 };
+
+			return (value, None);
+		}
+
+		if position.halfmove_clock() >= 100 {
+			return (0.5, None);
+		}
+
+		let scores: Vec<i32> = legal_moves.iter().map(|m| {
+			let mut moved_position = position.clone();
+			moved_position.make_move(m.clone());
+
+			-self.evaluator.evaluate(&moved_position)
+		}).collect();
+
+		let best_score = *scores.iter().max().unwrap();
+		let weights: Vec<f64> = scores.iter().map(|s| ((s - best_score) as f64 / PRIOR_TEMPERATURE).exp()).collect();
+		let weight_sum: f64 = weights.iter().sum();
+
+		let value = if self.rollout_depth > 0 { self.rollout(position) } else { win_probability(best_score) };
+		let children = legal_moves.into_iter().zip(weights).map(|(m, w)| (m, w / weight_sum)).collect();
+
+		(value, Some(children))
+	}
+
+	/// Win probability of the player to move in `position` after playing random moves from it.
+	fn rollout(&self, position: &Position) -> f64 {
+		let mut position = position.clone();
+		// The player to move alternates, so the value is flipped for odd plies
+		let for_root_player = |value: f64, ply: u32| if ply % 2 == 0 { value } else { 1.0 - value };
+
+		for ply in 0..self.rollout_depth {
+			let legal_moves = position.get_all_legal_moves();
+
+			let Some(moove) = legal_moves.choose(&mut rand::rng()) else {
+				let value = if position.is_in_check(position.current_player()) { 0.0 } else { 0.5 };
+
+				return for_root_player(value, ply);
+			};
+
+			position.make_move(moove.clone());
+		}
+
+		for_root_player(win_probability(self.evaluator.evaluate(&position)), self.rollout_depth)
+	}
+
+	/// Tree to start searching the last position of `history` with, keeping the previous search's if possible.
+	fn take_tree(&self, history: &[Position]) -> Tree {
+		let position = history.last().expect("history contains the position to search");
+		let previous_tree = self.tree.lock().unwrap().take();
+
+		previous_tree
+			.filter(|_| self.reuse_tree)
+			.and_then(|tree| tree.reuse(history))
+			.unwrap_or_else(|| Tree::new(position.clone()))
+	}
+
+	fn search_info(tree: &Tree, playouts: u64, time: Duration) -> SearchInfo {
+		let pv = tree.principal_variation();
+
+		SearchInfo {
+			multi_pv: 1,
+			depth: pv.len() as u32,
+			seldepth: tree.max_depth as u32,
+			score: tree.root_score(),
+			nodes: playouts,
+			nps: (playouts as f64 / time.as_secs_f64().max(0.001)) as u64,
+			hashfull: 0,
+			time,
+			pv,
+		}
+	}
+}
+
+impl Clone for MctsBot {
+	fn clone(&self) -> Self {
+		Self {
+			evaluator: self.evaluator.clone(),
+			exploration: self.exploration,
+			fpu_reduction: self.fpu_reduction,
+			virtual_loss: self.virtual_loss,
+			threads: self.threads,
+			rollout_depth: self.rollout_depth,
+			reuse_tree: self.reuse_tree,
+			max_tree_nodes: self.max_tree_nodes,
+			tree: self.tree.clone(),
+			stop: Arc::new(AtomicBool::new(false)),
+		}
+	}
+}
+
+impl Bot for MctsBot {
+	fn new_game(&mut self) {
+		*self.tree.lock().unwrap() = None;
+	}
+
+	fn options(&self) -> Vec<BotOption> {
+		vec![
+			BotOption::spin(EXPLORATION_OPTION, DEFAULT_EXPLORATION, 1, 1000, (self.exploration * 100.0).round() as i64),
+			BotOption::spin(FPU_REDUCTION_OPTION, DEFAULT_FPU_REDUCTION, 0, 100, (self.fpu_reduction * 100.0).round() as i64),
+			BotOption::spin(VIRTUAL_LOSS_OPTION, DEFAULT_VIRTUAL_LOSS, 0, 16, self.virtual_loss as i64),
+			BotOption::spin(THREADS_OPTION, 1, 1, MAX_THREADS, self.threads as i64),
+			BotOption::spin(ROLLOUT_DEPTH_OPTION, 0, 0, MAX_ROLLOUT_DEPTH, self.rollout_depth as i64),
+			BotOption::check(REUSE_TREE_OPTION, true, self.reuse_tree),
+			BotOption::spin(TREE_SIZE_OPTION, DEFAULT_TREE_SIZE, 1, MAX_TREE_SIZE, (self.max_tree_nodes / 1000) as i64),
+		]
+	}
+
+	fn set_option(&mut self, name: &str, value: &str) -> Result<(), OptionError> {
+		match name {
+			EXPLORATION_OPTION => self.exploration = BotOption::parse_spin(name, value, 1, 1000)? as f64 / 100.0,
+			FPU_REDUCTION_OPTION => self.fpu_reduction = BotOption::parse_spin(name, value, 0, 100)? as f64 / 100.0,
+			VIRTUAL_LOSS_OPTION => self.virtual_loss = BotOption::parse_spin(name, value, 0, 16)? as u32,
+			THREADS_OPTION => self.threads = BotOption::parse_spin(name, value, 1, MAX_THREADS)? as usize,
+			ROLLOUT_DEPTH_OPTION => self.rollout_depth = BotOption::parse_spin(name, value, 0, MAX_ROLLOUT_DEPTH)? as u32,
+			REUSE_TREE_OPTION => self.reuse_tree = BotOption::parse_check(name, value)?,
+			TREE_SIZE_OPTION => self.max_tree_nodes = BotOption::parse_spin(name, value, 1, MAX_TREE_SIZE)? as usize * 1000,
+			_ => return Err(OptionError::UnknownOption(name.to_owned())),
+		}
+
+		Ok(())
+	}
+
+	fn search(&self, history: &[Position], limits: &SearchLimits, observer: &dyn SearchObserver) -> SearchResult {
+		let start_time = Instant::now();
+		let time_limit = limits.active_time_control().map(|t| TimeManager::new(t).soft_limit());
+		let max_depth = limits.max_depth();
+
+		let tree = self.take_tree(history);
+		let previous_playouts = tree.nodes[0].visits as u64;
+		let tree = Mutex::new(tree);
+
+		let history: Vec<u64> = history.iter().map(|p| p.hash().value()).collect();
+		let done = AtomicBool::new(false);
+
+		let should_stop = |tree: &Tree| {
+			// The clock starts at the ponder hit when pondering
+			let clock_start = match &limits.ponder {
+				Some(ponder_hit) => ponder_hit.time(),
+				None => Some(start_time),
+			};
+
+			self.stop.load(Ordering::Relaxed)
+				|| time_limit.zip(clock_start).is_some_and(|(time_limit, clock_start)| clock_start.elapsed() >= time_limit)
+				|| limits.nodes.is_some_and(|nodes| tree.nodes[0].visits as u64 - previous_playouts >= nodes)
+				// Nothing beats mating right away
+				|| tree.root_score() == Score::mate_in(1)
+		};
+
+		thread::scope(|scope| {
+			for _ in 1..self.threads {
+				scope.spawn(|| {
+					while !done.load(Ordering::Relaxed) {
+						self.playout(&tree, &history[..history.len() - 1]);
+					}
+				});
+			}
+
+			let mut last_report = Instant::now();
+
+			loop {
+				self.playout(&tree, &history[..history.len() - 1]);
+
+				let locked_tree = tree.lock().unwrap();
+
+				if should_stop(&locked_tree) {
+					break;
+				}
+
+				// Walking the principal variation for the depth holds up the other threads, so it waits for the reports
+				if last_report.elapsed() >= REPORT_INTERVAL {
+					let playouts = locked_tree.nodes[0].visits as u64 - previous_playouts;
+					let info = Self::search_info(&locked_tree, playouts, start_time.elapsed());
+					let reached_depth = max_depth.is_some_and(|depth| info.depth >= depth);
+
+					observer.notify(SearchEvent::Iteration(info));
+					last_report = Instant::now();
+
+					if reached_depth {
+						break;
+					}
+				}
+			}
+
+			done.store(true, Ordering::Relaxed);
+		});
+
+		let tree = tree.into_inner().unwrap();
+		let playouts = tree.nodes[0].visits as u64 - previous_playouts;
+		let info = Self::search_info(&tree, playouts, start_time.elapsed());
+
+		observer.notify(SearchEvent::Iteration(info.clone()));
+
+		let best_child = tree.best_child(0).expect("position has legal moves");
+
+		let result = SearchResult {
+			best_move: tree.nodes[best_child].moove.clone().unwrap(),
+			score: info.score,
+			pv: info.pv,
+			stats: SearchStats {
+				depth: info.depth,
+				seldepth: info.seldepth,
+				nodes: playouts,
+				time: info.time,
+			},
+		};
+
+		if self.reuse_tree {
+			*self.tree.lock().unwrap() = Some(tree);
+		}
+
+		// Ready for the next search
+		self.stop.store(false, Ordering::Relaxed);
+
+		result
+	}
+
+	fn stop(&self) {
+		self.stop.store(true, Ordering::Relaxed);
+	}
+}
+
+#[derive(Clone)]
+struct Node {
+	/// Move leading to the node, [`None`] for the root.
+	moove: Option<Move>,
+	prior: f64,
+	visits: u32,
+	/// Sum of the results for the player who made the move leading here, each from 0 for a loss to 1 for a win.
+	value_sum: f64,
+	virtual_loss: u32,
+	/// Empty until the node is expanded.
+	children: Vec<usize>,
+	/// Value for the player to move once the game is known to be over here.
+	terminal: Option<f64>,
+}
+
+impl Node {
+	fn new(moove: Option<Move>, prior: f64) -> Self {
+		Self {
+			moove,
+			prior,
+			visits: 0,
+			value_sum: 0.0,
+			virtual_loss: 0,
+			children: Vec::new(),
+			terminal: None,
+		}
+	}
+}
+
+struct Tree {
+	root_position: Position,
+	/// Nodes with the root first. Children always come after their parent.
+	nodes: Vec<Node>,
+	/// Deepest ply selected from the root, including by earlier searches of a reused tree.
+	max_depth: usize,
+}
+
+impl Tree {
+	fn new(root_position: Position) -> Self {
+		Self {
+			root_position,
+			nodes: vec![Node::new(None, 1.0)],
+			max_depth: 0,
+		}
+	}
+
+	/// Child of `parent` with the highest PUCT value. Pending virtual losses count as visits which were lost.
+	fn select_child(&self, parent: usize, exploration: f64, fpu_reduction: f64) -> usize {
+		let parent_node = &self.nodes[parent];
+		let parent_visits = (parent_node.visits + parent_node.virtual_loss) as f64;
+
+		// Value of the parent for its player to move, assumed for moves without visits minus the reduction
+		let parent_value = if parent_node.visits > 0 { 1.0 - parent_node.value_sum / parent_node.visits as f64 } else { 0.5 };
+		let first_play_value = (parent_value - fpu_reduction).max(0.0);
+
+		let puct = |child: &Node| {
+			let visits = (child.visits + child.virtual_loss) as f64;
+			let value = if visits > 0.0 { child.value_sum / visits } else { first_play_value };
+
+			value + exploration * child.prior * parent_visits.sqrt() / (1.0 + visits)
+		};
+
+		*parent_node.children.iter().max_by(|a, b| puct(&self.nodes[**a]).total_cmp(&puct(&self.nodes[**b]))).unwrap()
+	}
+
+	fn expand(&mut self, index: usize, children: Vec<(Move, f64)>) {
+		for (moove, prior) in children {
+			self.nodes.push(Node::new(Some(moove), prior));
+			let child = self.nodes.len() - 1;
+			self.nodes[index].children.push(child);
+		}
+	}
+
+	/// Add `value`, the result for the player to move at the end of `path`, to every node on it.
+	fn backpropagate(&mut self, path: &[usize], mut value: f64, virtual_loss: u32) {
+		for &index in path.iter().rev() {
+			let node = &mut self.nodes[index];
+
+			node.visits += 1;
+			node.value_sum += 1.0 - value;
+
+			// The root isn't selected, so it never gets a virtual loss
+			if index != 0 {
+				node.virtual_loss -= virtual_loss;
+			}
+
+			value = 1.0 - value;
+		}
+	}
+
+	/// Most visited child of `index`, the one with the highest prior among equally visited ones.
+	fn best_child(&self, index: usize) -> Option<usize> {
+		self.nodes[index].children.iter().copied().max_by(|a, b| {
+			let (a, b) = (&self.nodes[*a], &self.nodes[*b]);
+
+			a.visits.cmp(&b.visits).then(a.prior.total_cmp(&b.prior))
+		})
+	}
+
+	/// Moves of the most visited children from the root on.
+	fn principal_variation(&self) -> Vec<Move> {
+		let mut pv = Vec::new();
+		let mut index = 0;
+
+		while let Some(child) = self.best_child(index) {
+			if self.nodes[child].visits == 0 {
+				break;
+			}
+
+			pv.push(self.nodes[child].moove.clone().unwrap());
+			index = child;
+		}
+
+		pv
+	}
+
+	/// Score of the root's best move, converted back from the win probability.
+	fn root_score(&self) -> Score {
+		let Some(best_child) = self.best_child(0) else {
+			return Score::new(0);
+		};
+
+		let node = &self.nodes[best_child];
+
+		// The player to move after the best move is checkmated
+		if node.terminal == Some(0.0) {
+			return Score::mate_in(1);
+		}
+
+		if node.visits == 0 {
+			return Score::new(0);
+		}
+
+		Score::new(centipawns(node.value_sum / node.visits as f64))
+	}
+
+	/// Part of the tree for the last position of `history`, if the root is one of the positions up to two plies before it.
+	fn reuse(self, history: &[Position]) -> Option<Tree> {
+		let root_hash = self.root_position.hash().value();
+		let start = history.iter().rposition(|p| p.hash().value() == root_hash)?;
+
+		if history.len() - 1 - start > 2 {
+			return None;
+		}
+
+		let mut index = 0;
+		let mut position = self.root_position.clone();
+
+		for next_position in &history[start + 1..] {
+			let next_hash = next_position.hash().value();
+
+			index = self.nodes[index].children.iter().copied().find(|&child| {
+				let mut moved_position = position.clone();
+				moved_position.make_move(self.nodes[child].moove.clone().unwrap());
+
+				moved_position.hash().value() == next_hash
+			})?;
+
+			position = next_position.clone();
+		}
+
+		let mut nodes = Vec::new();
+		let mut max_depth = 0;
+		self.copy_subtree(index, 0, &mut nodes, &mut max_depth);
+		nodes[0].moove = None;
+
+		Some(Tree {
+			root_position: position,
+			nodes,
+			max_depth,
+		})
+	}
+
+	/// Append the subtree of `index` at `depth` to `nodes`, returning the new index of its root. Raises `max_depth` to
+	/// the depth of its deepest visited node.
+	fn copy_subtree(&self, index: usize, depth: usize, nodes: &mut Vec<Node>, max_depth: &mut usize) -> usize {
+		let new_index = nodes.len();
+
+		nodes.push(Node {
+			children: Vec::new(),
+			..self.nodes[index].clone()
+		});
+
+		if self.nodes[index].visits > 0 {
+			*max_depth = (*max_depth).max(depth);
+		}
+
+		let children = self.nodes[index].children.iter().map(|&child| self.copy_subtree(child, depth + 1, nodes, max_depth)).collect();
+		nodes[new_index].children = children;
+
+		new_index
+	}
+}
+
+/// Win probability of a player with an evaluation of `centipawns`.
+fn win_probability(centipawns: i32) -> f64 {
+	1.0 / (1.0 + 10f64.powf(-centipawns as f64 / WIN_PROBABILITY_SCALE))
+}
+
+/// Evaluation giving a win probability of `probability`, the inverse of [`win_probability`].
+fn centipawns(probability: f64) -> i32 {
+	let probability = probability.clamp(0.001, 0.999);
+
+	(-WIN_PROBABILITY_SCALE * (1.0 / probability - 1.0).log10()) as i32
+}
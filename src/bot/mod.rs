@@ -1,9 +1,19 @@
 //! Logic for evaluation and search algorithms.
 
-use std::time::Duration;
-
+use baseline_bots::{AlphaBetaBot, GreedyBot, NegamaxBot, RandomBot};
+use bot_option::{BotOption, OptionError};
 use dyn_clone::DynClone;
+use eval_params::EvalParams;
 use evaluation::Evaluation;
+use evaluator::ClassicalEvaluator;
+use iterative_deepening_search::IterativeDeepeningSearch;
+use limited_strength::{LimitedStrength, Strength};
+use mcts::MctsBot;
+use nnue::NnueEvaluator;
+use search_limits::SearchLimits;
+use search_observer::SearchObserver;
+use search_options::SearchOptions;
+use search_result::SearchResult;
 
 use crate::board::{moove::Move, position::Position};
 
@@ -14,10 +24,74 @@ pub mod positioning;
 pub mod utils;
 pub mod transposition_table;
 pub mod iterative_deepening_search;
+pub mod search_options;
+pub mod move_ordering;
+pub mod score;
+pub mod time_manager;
+pub mod search_limits;
+pub mod search_observer;
+pub mod search_result;
+pub mod bot_option;
+pub mod limited_strength;
+pub mod baseline_bots;
+pub mod mcts;
+pub mod evaluator;
+pub mod eval_params;
+pub mod pawn_structure;
+pub mod king_safety;
+pub mod piece_activity;
+pub mod endgame;
+pub mod nnue;
+pub mod eval_trace;
 
 pub type EvaluationFn = fn(&Position) -> Evaluation;
 pub type SearchFn = fn(&Position, EvaluationFn, u32) -> (Move, Evaluation);
 
-pub trait Bot: DynClone + Send {
-    fn search_best_move(&self, position: &Position, search_time: Duration) -> (i32, Move);
-}
\ No newline at end of file
+/// Chess engine which can be driven by any frontend.
+///
+/// Frontends configure a bot with [`Bot::set_option`], call [`Bot::new_game`] between games and run [`Bot::search`]
+/// on another thread, which can be ended early with [`Bot::stop`].
+pub trait Bot: DynClone + Send + Sync {
+    /// Forget everything remembered from the previous game, like transposition table entries.
+    fn new_game(&mut self) {}
+
+    /// Options of the bot with their current values.
+    fn options(&self) -> Vec<BotOption> {
+        Vec::new()
+    }
+
+    /// Set the option called `name` to `value`. Button options ignore the value.
+    fn set_option(&mut self, name: &str, _value: &str) -> Result<(), OptionError> {
+        Err(OptionError::UnknownOption(name.to_owned()))
+    }
+
+    /// Search for the best move in the last position of `history`, which holds every position of the game so far,
+    /// within `limits`. Progress is reported to `observer`. The game must not have ended in that position.
+    fn search(&self, history: &[Position], limits: &SearchLimits, observer: &dyn SearchObserver) -> SearchResult;
+
+    /// Make a running [`Bot::search`] return as soon as possible with the best move found so far.
+    fn stop(&self);
+}
+
+/// Names of the bots [`create_bot`] knows, starting with the default.
+pub const BOT_NAMES: [&str; 6] = ["search", "mcts", "random", "greedy", "alpha-beta", "negamax"];
+
+/// Create the bot called `name`, one of [`BOT_NAMES`]. Only the main search uses `search_options` and `strength`, the
+/// baseline bots ignore `eval_params`.
+pub fn create_bot(name: &str, search_options: SearchOptions, strength: Strength, eval_params: EvalParams) -> Option<Box<dyn Bot>> {
+    let bot: Box<dyn Bot> = match name {
+        "search" => {
+            let bot = IterativeDeepeningSearch::with_options(NnueEvaluator::new(ClassicalEvaluator::new(eval_params)), true, search_options);
+
+            Box::new(LimitedStrength::new(bot, strength))
+        },
+        "mcts" => Box::new(MctsBot::new(NnueEvaluator::new(ClassicalEvaluator::new(eval_params)))),
+        "random" => Box::new(RandomBot),
+        "greedy" => Box::new(GreedyBot),
+        "alpha-beta" => Box::new(AlphaBetaBot::new(4)),
+        "negamax" => Box::new(NegamaxBot::new(3)),
+        _ => return None,
+    };
+
+    Some(bot)
+}
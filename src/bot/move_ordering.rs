@@ -0,0 +1,83 @@
+//! Move ordering for the search. Trying the best moves first makes alpha-beta cut off sooner.
+
+use crate::{board::{moove::Move, position::Position}, piece::PieceType};
+
+const TRANSPOSITION_MOVE_SCORE: i32 = 1000000;
+const CAPTURE_SCORE: i32 = 100000;
+const PROMOTION_SCORE: i32 = 90000;
+const FIRST_KILLER_SCORE: i32 = 80000;
+const SECOND_KILLER_SCORE: i32 = 79000;
+
+/// Value of a piece used to order captures.
+pub fn piece_order_value(piece: PieceType) -> i32 {
+	match piece {
+		PieceType::Pawn => 1,
+		PieceType::Knight => 3,
+		PieceType::Bishop => 3,
+		PieceType::Rook => 5,
+		PieceType::Queen => 9,
+		PieceType::King => 100,
+	}
+}
+
+/// Return `true` if the move captures a piece.
+pub fn is_capture(position: &Position, moove: &Move) -> bool {
+	match moove {
+		Move::EnPassant(_) => true,
+		Move::Castling(_) => false,
+		_ => position.get_piece(moove.to_position()).is_some(),
+	}
+}
+
+/// Return `true` if the move neither captures nor promotes.
+pub fn is_quiet(position: &Position, moove: &Move) -> bool {
+	!matches!(moove, Move::Promoting(_)) && !is_capture(position, moove)
+}
+
+/// Score used to sort a move. Higher scores are searched first.
+fn score_move(position: &Position, moove: &Move, transposition_move: Option<&Move>, killers: &[Option<Move>; 2]) -> i32 {
+	if transposition_move == Some(moove) {
+		return TRANSPOSITION_MOVE_SCORE;
+	}
+
+	if is_capture(position, moove) {
+		let victim = match moove {
+			Move::EnPassant(_) => PieceType::Pawn,
+			_ => position.get_piece(moove.to_position()).unwrap().piece(),
+		};
+
+		let attacker = position.get_piece(moove.from_position()).unwrap().piece();
+
+		// Most valuable victim, least valuable attacker
+		return CAPTURE_SCORE + piece_order_value(victim) * 10 - piece_order_value(attacker);
+	}
+
+	if let Move::Promoting(promoting_move) = moove {
+		return PROMOTION_SCORE + piece_order_value(promoting_move.promotion_piece().piece());
+	}
+
+	if killers[0].as_ref() == Some(moove) {
+		return FIRST_KILLER_SCORE;
+	}
+
+	if killers[1].as_ref() == Some(moove) {
+		return SECOND_KILLER_SCORE;
+	}
+
+	0
+}
+
+/// Sort `moves` so that the transposition table move comes first, followed by captures, promotions, killer moves and quiet moves.
+pub fn order_moves(position: &Position, moves: &mut Vec<Move>, transposition_move: Option<&Move>, killers: &[Option<Move>; 2]) {
+	moves.sort_by_cached_key(|m| -score_move(position, m, transposition_move, killers));
+}
+
+/// Remember a quiet move which caused a beta cutoff at this ply.
+pub fn store_killer(killers: &mut [Option<Move>; 2], moove: &Move) {
+	if killers[0].as_ref() == Some(moove) {
+		return;
+	}
+
+	killers[1] = killers[0].take();
+	killers[0] = Some(moove.clone());
+}
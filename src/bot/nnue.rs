@@ -0,0 +1,283 @@
+//! Efficiently updatable neural network evaluation.
+//!
+//! The network has 768 inputs, one for each piece type and colour on each square, a hidden layer of
+//! [`HIDDEN_SIZE`] neurons computed from both players' perspectives and a single output. The hidden layer before
+//! activation is kept in [`Accumulator`]s, which [`Position`] updates with every move while a network is loaded.
+//!
+//! Network files are little-endian: the bytes `CNUE`, the hidden size as `u32`, then the `i16` input weights of each
+//! input for every hidden neuron, the `i16` hidden biases, the `i16` output weights of the player to move's half of
+//! the hidden layer followed by the other half, and the `i32` output bias. Hidden values are quantised by
+//! [`QA`], output weights by [`QB`] and the output bias by both.
+
+use std::{fmt, fs, io, path::Path, sync::OnceLock};
+
+use crate::{board::{board::Board, moove::{BasicMove, CastlingMove, EnPassantMove, PromotingMove}, position::Position, tile_position::TilePosition}, piece::PieceType, player::Player, player_piece::PlayerPiece};
+
+use super::evaluator::{ClassicalEvaluator, Evaluator};
+
+pub const HIDDEN_SIZE: usize = 128;
+pub const INPUT_SIZE: usize = 768;
+
+/// Quantisation of the hidden layer, activations are clipped to `0..=QA`.
+pub const QA: i32 = 255;
+/// Quantisation of the output weights.
+pub const QB: i32 = 64;
+/// Centipawns of an output of 1.
+pub const SCALE: i32 = 400;
+
+const MAGIC: &[u8; 4] = b"CNUE";
+
+/// The loaded network, shared by all positions and searches.
+static NETWORK: OnceLock<Network> = OnceLock::new();
+
+/// The network loaded by [`load_network`].
+pub fn network() -> Option<&'static Network> {
+	NETWORK.get()
+}
+
+/// Load the network used by every [`NnueEvaluator`]. Only one network can be loaded and positions made before
+/// loading it aren't updated incrementally, so this should happen at startup.
+pub fn load_network(path: impl AsRef<Path>) -> Result<(), NnueError> {
+	let network = Network::load(path)?;
+
+	NETWORK.set(network).map_err(|_| NnueError::AlreadyLoaded)
+}
+
+pub struct Network {
+	/// Weights of each input for every hidden neuron.
+	input_weights: Vec<[i16; HIDDEN_SIZE]>,
+	hidden_biases: [i16; HIDDEN_SIZE],
+	/// Weights of the player to move's half of the hidden layer and the other player's half.
+	output_weights: [[i16; HIDDEN_SIZE]; 2],
+	output_bias: i32,
+}
+
+impl Network {
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, NnueError> {
+		let bytes = fs::read(path).map_err(NnueError::Io)?;
+
+		Self::from_bytes(&bytes)
+	}
+
+	/// Read a network in the format of the module documentation.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, NnueError> {
+		if bytes.len() < 8 || &bytes[0..4] != MAGIC {
+			return Err(NnueError::InvalidHeader);
+		}
+
+		let hidden_size = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+
+		if hidden_size != HIDDEN_SIZE {
+			return Err(NnueError::WrongHiddenSize { expected: HIDDEN_SIZE, found: hidden_size });
+		}
+
+		let expected = 8 + (INPUT_SIZE + 3) * HIDDEN_SIZE * 2 + 4;
+
+		if bytes.len() != expected {
+			return Err(NnueError::WrongLength { expected, found: bytes.len() });
+		}
+
+		let mut values = bytes[8..expected - 4].chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]]));
+		let mut next_row = || {
+			let mut row = [0; HIDDEN_SIZE];
+			row.iter_mut().for_each(|v| *v = values.next().unwrap());
+			row
+		};
+
+		let input_weights = (0..INPUT_SIZE).map(|_| next_row()).collect();
+		let hidden_biases = next_row();
+		let output_weights = [next_row(), next_row()];
+		let output_bias = i32::from_le_bytes(bytes[expected - 4..].try_into().unwrap());
+
+		Ok(Self {
+			input_weights,
+			hidden_biases,
+			output_weights,
+			output_bias,
+		})
+	}
+
+	/// Write the network in the format of the module documentation.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = MAGIC.to_vec();
+
+		bytes.extend((HIDDEN_SIZE as u32).to_le_bytes());
+
+		for row in self.input_weights.iter().chain([&self.hidden_biases]).chain(&self.output_weights) {
+			bytes.extend(row.iter().flat_map(|v| v.to_le_bytes()));
+		}
+
+		bytes.extend(self.output_bias.to_le_bytes());
+
+		bytes
+	}
+
+	/// Score of `position` in centipawns from the point of view of the player to move.
+	pub fn evaluate(&self, position: &Position) -> i32 {
+		let fresh;
+		let accumulator = match position.nnue_accumulator() {
+			Some(accumulator) => accumulator,
+			// Made before the network was loaded
+			None => {
+				fresh = Accumulator::new(self, position.board());
+				&fresh
+			},
+		};
+
+		let (own, other) = match position.current_player() {
+			Player::White => (&accumulator.values[0], &accumulator.values[1]),
+			Player::Black => (&accumulator.values[1], &accumulator.values[0]),
+		};
+
+		// Can exceed an i32 before the division with large weights
+		let output = dot_activated(own, &self.output_weights[0]) as i64 + dot_activated(other, &self.output_weights[1]) as i64;
+
+		return ((output + self.output_bias as i64) * SCALE as i64 / (QA * QB) as i64) as i32;
+	}
+}
+
+/// Sum of the clipped `values` times `weights`. Plain loops over fixed-size arrays, which the compiler vectorises. Fits an
+/// i32, since each product is at most `QA * 32768`.
+fn dot_activated(values: &[i16; HIDDEN_SIZE], weights: &[i16; HIDDEN_SIZE]) -> i32 {
+	let mut sum = 0;
+
+	for i in 0..HIDDEN_SIZE {
+		sum += values[i].clamp(0, QA as i16) as i32 * weights[i] as i32;
+	}
+
+	return sum;
+}
+
+/// Input of `piece` on `tile_position` seen by `perspective`, who sees the board from their own side.
+fn input_index(perspective: Player, piece: PlayerPiece, tile_position: TilePosition) -> usize {
+	let (square, side) = match perspective {
+		Player::White => (tile_position.bit_offset(), piece.player() as usize),
+		Player::Black => (tile_position.bit_offset() ^ 56, piece.player().opposite() as usize),
+	};
+
+	return (side * 6 + piece.piece() as usize) * 64 + square as usize;
+}
+
+/// Hidden layer before activation from White's and Black's perspective. Values wrap around on overflow like in other
+/// NNUE engines, the network is expected to be trained so they don't.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Accumulator {
+	values: [[i16; HIDDEN_SIZE]; 2],
+}
+
+impl Accumulator {
+	pub fn new(network: &Network, board: &Board) -> Self {
+		let mut s = Self {
+			values: [network.hidden_biases; 2],
+		};
+
+		for bit_offset in 0..64 {
+			let tile_position = TilePosition::from_bit_offset(bit_offset);
+
+			if let Some(piece) = board.get_piece(tile_position) {
+				s.add_piece(network, piece, tile_position);
+			}
+		}
+
+		s
+	}
+
+	pub fn update_basic_move(&mut self, network: &Network, basic_move: BasicMove, moved_piece: PlayerPiece, captured_piece: Option<PlayerPiece>) {
+		self.remove_piece(network, moved_piece, basic_move.from_position());
+
+		if let Some(p) = captured_piece {
+			self.remove_piece(network, p, basic_move.to_position());
+		}
+
+		self.add_piece(network, moved_piece, basic_move.to_position());
+	}
+
+	pub fn update_castling_move(&mut self, network: &Network, castling_move: CastlingMove, king_piece: PlayerPiece) {
+		let rook_piece = PlayerPiece::new(king_piece.player(), PieceType::Rook);
+
+		self.remove_piece(network, king_piece, castling_move.from_position());
+		self.remove_piece(network, rook_piece, castling_move.rook_from_position());
+		self.add_piece(network, king_piece, castling_move.to_position());
+		self.add_piece(network, rook_piece, castling_move.rook_to_position());
+	}
+
+	pub fn update_en_passant_move(&mut self, network: &Network, en_passant_move: EnPassantMove, moved_piece: PlayerPiece, captured_piece: PlayerPiece) {
+		self.remove_piece(network, moved_piece, en_passant_move.from_position());
+		self.remove_piece(network, captured_piece, en_passant_move.captured_tile());
+		self.add_piece(network, moved_piece, en_passant_move.to_position());
+	}
+
+	pub fn update_promoting_move(&mut self, network: &Network, promoting_move: PromotingMove, moved_piece: PlayerPiece, captured_piece: Option<PlayerPiece>) {
+		self.remove_piece(network, moved_piece, promoting_move.from_position());
+
+		if let Some(p) = captured_piece {
+			self.remove_piece(network, p, promoting_move.to_position());
+		}
+
+		self.add_piece(network, promoting_move.promotion_piece(), promoting_move.to_position());
+	}
+
+	fn add_piece(&mut self, network: &Network, piece: PlayerPiece, tile_position: TilePosition) {
+		for (perspective, values) in [Player::White, Player::Black].into_iter().zip(&mut self.values) {
+			let weights = &network.input_weights[input_index(perspective, piece, tile_position)];
+
+			for i in 0..HIDDEN_SIZE {
+				values[i] = values[i].wrapping_add(weights[i]);
+			}
+		}
+	}
+
+	fn remove_piece(&mut self, network: &Network, piece: PlayerPiece, tile_position: TilePosition) {
+		for (perspective, values) in [Player::White, Player::Black].into_iter().zip(&mut self.values) {
+			let weights = &network.input_weights[input_index(perspective, piece, tile_position)];
+
+			for i in 0..HIDDEN_SIZE {
+				values[i] = values[i].wrapping_sub(weights[i]);
+			}
+		}
+	}
+}
+
+/// Evaluates with the loaded network, or with the hand-written evaluation while there is none.
+#[derive(Clone)]
+pub struct NnueEvaluator {
+	fallback: ClassicalEvaluator,
+}
+
+impl NnueEvaluator {
+	pub fn new(fallback: ClassicalEvaluator) -> Self {
+		Self {
+			fallback
+		}
+	}
+}
+
+impl Evaluator for NnueEvaluator {
+	fn evaluate(&self, position: &Position) -> i32 {
+		match network() {
+			Some(network) => network.evaluate(position),
+			None => self.fallback.evaluate(position),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum NnueError {
+	Io(io::Error),
+	InvalidHeader,
+	WrongHiddenSize { expected: usize, found: usize },
+	WrongLength { expected: usize, found: usize },
+	AlreadyLoaded,
+}
+
+impl fmt::Display for NnueError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Io(error) => write!(f, "couldn't read network: {}", error),
+			Self::InvalidHeader => write!(f, "not a network file"),
+			Self::WrongHiddenSize { expected, found } => write!(f, "network has {} hidden neurons, expected {}", found, expected),
+			Self::WrongLength { expected, found } => write!(f, "network file has {} bytes, expected {}", found, expected),
+			Self::AlreadyLoaded => write!(f, "a network is already loaded"),
+		}
+	}
+}
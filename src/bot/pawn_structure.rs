@@ -0,0 +1,293 @@
+//! Pawn structure evaluation and the table caching it.
+//!
+//! Both sides are evaluated as if they were White moving up the board, Black's pawns are mirrored first.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{board::{bitboard::Bitboard, position::Position}, player::Player};
+
+use super::eval_params::EvalParams;
+
+/// Entries in a [`PawnTable`], about 1.5 MB.
+const PAWN_TABLE_SIZE: usize = 1 << 16;
+
+const FIRST_RANK: u64 = 0xFF;
+
+/// Terms depending only on the pawns, from White's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PawnStructure {
+	/// Midgame and endgame score.
+	pub score: (i32, i32),
+	/// Passed pawns of both players.
+	pub passed_pawns: Bitboard,
+}
+
+impl PawnStructure {
+	pub fn new(params: &EvalParams, position: &Position) -> Self {
+		let board = position.board();
+		let white_pawns = (board.pawns & *board.get_player_bitboard(Player::White)).0;
+		let black_pawns = (board.pawns & *board.get_player_bitboard(Player::Black)).0;
+
+		let (white_score, white_passed) = evaluate_side(params, white_pawns, black_pawns);
+		let (black_score, black_passed) = evaluate_side(params, black_pawns.swap_bytes(), white_pawns.swap_bytes());
+
+		Self {
+			score: (white_score.0 - black_score.0, white_score.1 - black_score.1),
+			passed_pawns: Bitboard(white_passed | black_passed.swap_bytes()),
+		}
+	}
+}
+
+/// Score of the pawn structure of `position` from White's point of view, including the passed pawn terms which depend
+/// on the other pieces. Looks up the pawn-only terms in `pawn_table` if it is given.
+pub fn evaluate_pawn_structure(params: &EvalParams, position: &Position, pawn_table: Option<&PawnTable>) -> (i32, i32) {
+	let pawn_key = position.hash().pawn_value();
+
+	let structure = match pawn_table.and_then(|table| table.get(pawn_key)) {
+		Some(structure) => structure,
+		None => {
+			let structure = PawnStructure::new(params, position);
+
+			if let Some(table) = pawn_table {
+				table.store(pawn_key, &structure);
+			}
+
+			structure
+		},
+	};
+
+	let board = position.board();
+	let occupied = board.get_all_pieces_mask().0;
+	let white_king = (board.kings & *board.get_player_bitboard(Player::White)).0;
+	let black_king = (board.kings & *board.get_player_bitboard(Player::Black)).0;
+	let white_pieces = board.get_player_bitboard(Player::White).0;
+
+	let white_passed = structure.passed_pawns.0 & white_pieces;
+	let black_passed = structure.passed_pawns.0 & !white_pieces;
+
+	let white_score = passed_pawn_path(params, white_passed, occupied, white_king, black_king);
+	let black_score = passed_pawn_path(params, black_passed.swap_bytes(), occupied.swap_bytes(), black_king.swap_bytes(), white_king.swap_bytes());
+
+	return (structure.score.0 + white_score.0 - black_score.0, structure.score.1 + white_score.1 - black_score.1);
+}
+
+/// Score of `player`'s pawn structure alone, computed without a table. [`evaluate_pawn_structure`] is the difference
+/// of both players' scores.
+pub fn side_pawn_structure(params: &EvalParams, position: &Position, player: Player) -> (i32, i32) {
+	let board = position.board();
+
+	// Seen from White's side, like the rest of the pawn structure
+	let relative = |bitboard: u64| if player == Player::White { bitboard } else { bitboard.swap_bytes() };
+	let own_pawns = relative((board.pawns & *board.get_player_bitboard(player)).0);
+	let enemy_pawns = relative((board.pawns & *board.get_player_bitboard(player.opposite())).0);
+	let own_king = relative((board.kings & *board.get_player_bitboard(player)).0);
+	let enemy_king = relative((board.kings & *board.get_player_bitboard(player.opposite())).0);
+
+	let (score, passed) = evaluate_side(params, own_pawns, enemy_pawns);
+	let path_score = passed_pawn_path(params, passed, relative(board.get_all_pieces_mask().0), own_king, enemy_king);
+
+	return (score.0 + path_score.0, score.1 + path_score.1);
+}
+
+/// Pawn-only terms of the player owning `own` pawns, who moves up the board. Returns the score and passed pawns.
+fn evaluate_side(params: &EvalParams, own: u64, enemy: u64) -> ((i32, i32), u64) {
+	let mut score = (0, 0);
+	let mut passed_pawns = 0;
+
+	let mut add = |weight: (i32, i32)| {
+		score.0 += weight.0;
+		score.1 += weight.1;
+	};
+
+	if own == 0 {
+		add(params.no_pawns);
+	}
+
+	let mut pawns = own;
+
+	while pawns != 0 {
+		let square = pawns.trailing_zeros();
+		pawns &= pawns - 1;
+
+		let file = square % 8;
+		let rank = square / 8;
+		let adjacent_files = adjacent_files(file);
+		let front = file_mask(file) & ranks_above(rank);
+
+		let doubled = own & front != 0;
+		let isolated = own & adjacent_files == 0;
+		// Own pawns next to or behind it on the neighbouring files, which can defend it
+		let supporters = own & adjacent_files & !ranks_above(rank);
+
+		if doubled {
+			add(params.doubled_pawn);
+		}
+
+		if isolated {
+			add(params.isolated_pawn);
+		}
+
+		if own & adjacent_files & (rank_mask(rank) | rank_mask(rank.saturating_sub(1))) != 0 {
+			add(params.connected_pawn[rank as usize]);
+		}
+
+		if doubled {
+			continue;
+		}
+
+		if enemy & (front | adjacent_files & ranks_above(rank)) == 0 {
+			add(params.passed_pawn[rank as usize]);
+			passed_pawns |= 1 << square;
+			continue;
+		}
+
+		// The square in front is guarded by an enemy pawn and no own pawn can come to help
+		if !isolated && supporters == 0 && enemy & adjacent_files & rank_mask(rank + 2) != 0 {
+			add(params.backward_pawn);
+		}
+
+		// Nothing blocks the file and there are enough own pawns to trade off the enemy pawns guarding it
+		let sentries = enemy & adjacent_files & ranks_above(rank);
+
+		if enemy & front == 0 && supporters.count_ones() >= sentries.count_ones() {
+			add(params.candidate_passed_pawn[rank as usize]);
+		}
+	}
+
+	return (score, passed_pawns);
+}
+
+/// Bonuses of the `passed` pawns of the player moving up the board for a free way to promotion and for the kings'
+/// distances to the square in front of them. Both grow the further the pawn has advanced.
+fn passed_pawn_path(params: &EvalParams, passed: u64, occupied: u64, own_king: u64, enemy_king: u64) -> (i32, i32) {
+	let mut score = (0, 0);
+	let mut pawns = passed;
+
+	while pawns != 0 {
+		let square = pawns.trailing_zeros();
+		pawns &= pawns - 1;
+
+		let rank = square / 8;
+		let advance = rank as i32 - 1;
+		let stop_square = square + 8;
+
+		let mut weight = (0, 0);
+
+		if occupied & file_mask(square % 8) & ranks_above(rank) == 0 {
+			weight = params.passed_pawn_free_path;
+		}
+
+		let own_distance = distance(own_king.trailing_zeros(), stop_square);
+		let enemy_distance = distance(enemy_king.trailing_zeros(), stop_square);
+
+		weight.0 += params.passed_pawn_own_king_distance.0 * own_distance + params.passed_pawn_enemy_king_distance.0 * enemy_distance;
+		weight.1 += params.passed_pawn_own_king_distance.1 * own_distance + params.passed_pawn_enemy_king_distance.1 * enemy_distance;
+
+		score.0 += weight.0 * advance;
+		score.1 += weight.1 * advance;
+	}
+
+	return score;
+}
+
+/// Number of king moves between two squares. Missing kings, only found in test positions, count as far away.
+pub fn distance(from: u32, to: u32) -> i32 {
+	if from >= 64 {
+		return 7;
+	}
+
+	let files = (from % 8).abs_diff(to % 8);
+	let ranks = (from / 8).abs_diff(to / 8);
+
+	return files.max(ranks) as i32;
+}
+
+pub fn file_mask(file: u32) -> u64 {
+	Bitboard::generate_column_mask(file).0
+}
+
+pub fn adjacent_files(file: u32) -> u64 {
+	let mut mask = 0;
+
+	if file > 0 {
+		mask |= file_mask(file - 1);
+	}
+
+	if file < 7 {
+		mask |= file_mask(file + 1);
+	}
+
+	mask
+}
+
+fn rank_mask(rank: u32) -> u64 {
+	if rank > 7 {
+		return 0;
+	}
+
+	FIRST_RANK << (rank * 8)
+}
+
+/// Every rank after `rank`.
+pub fn ranks_above(rank: u32) -> u64 {
+	if rank >= 7 {
+		return 0;
+	}
+
+	u64::MAX << ((rank + 1) * 8)
+}
+
+/// Lossy cache of [`PawnStructure`]s by the pawn-only Zobrist hash, shared between search threads without locking.
+/// Entries overwritten by another thread while being read are detected by their hash check.
+pub struct PawnTable {
+	entries: Vec<PawnTableEntry>,
+}
+
+struct PawnTableEntry {
+	/// Key xor both values.
+	hash_check: AtomicU64,
+	score: AtomicU64,
+	passed_pawns: AtomicU64,
+}
+
+impl PawnTable {
+	pub fn new() -> Self {
+		let entries = (0..PAWN_TABLE_SIZE).map(|_| PawnTableEntry {
+			// Empty entries match no likely key, not even the key of positions without pawns
+			hash_check: AtomicU64::new(u64::MAX),
+			score: AtomicU64::new(0),
+			passed_pawns: AtomicU64::new(0),
+		}).collect();
+
+		Self {
+			entries
+		}
+	}
+
+	pub fn get(&self, pawn_key: u64) -> Option<PawnStructure> {
+		let entry = &self.entries[pawn_key as usize % self.entries.len()];
+
+		let score = entry.score.load(Ordering::Relaxed);
+		let passed_pawns = entry.passed_pawns.load(Ordering::Relaxed);
+
+		if entry.hash_check.load(Ordering::Relaxed) ^ score ^ passed_pawns != pawn_key {
+			return None;
+		}
+
+		Some(PawnStructure {
+			score: (score as u32 as i32, (score >> 32) as u32 as i32),
+			passed_pawns: Bitboard(passed_pawns),
+		})
+	}
+
+	pub fn store(&self, pawn_key: u64, structure: &PawnStructure) {
+		let entry = &self.entries[pawn_key as usize % self.entries.len()];
+
+		let score = structure.score.0 as u32 as u64 | (structure.score.1 as u32 as u64) << 32;
+		let passed_pawns = structure.passed_pawns.0;
+
+		entry.score.store(score, Ordering::Relaxed);
+		entry.passed_pawns.store(passed_pawns, Ordering::Relaxed);
+		entry.hash_check.store(pawn_key ^ score ^ passed_pawns, Ordering::Relaxed);
+	}
+}
@@ -0,0 +1,170 @@
+//! Piece activity evaluation: mobility from attack bitboards, outposts, rooks on the 7th rank, trapped pieces and the
+//! value of minor pieces by the number of pawns.
+
+use crate::{board::{bitboard::Bitboard, position::Position, tile_position::TilePosition}, piece::PieceType, player::Player, player_piece::PlayerPiece};
+
+use super::{eval_params::EvalParams, pawn_structure::{adjacent_files, ranks_above}};
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = FILE_A << 7;
+
+/// Pieces whose mobility is counted.
+const MOBILE_PIECES: [PieceType; 4] = [PieceType::Rook, PieceType::Knight, PieceType::Bishop, PieceType::Queen];
+
+/// Usual number of safe squares of each piece type, indexed like [`PieceType`]. Mobility is scored relative to it, so
+/// that an average piece keeps its material value.
+const MOBILITY_BASELINE: [i32; 6] = [0, 6, 4, 6, 12, 0];
+
+/// Pawn count at which knights and bishops are worth their material value.
+const BASE_PAWN_COUNT: i32 = 5;
+
+/// Outposts are on the 4th to the 6th rank from the own side.
+const OUTPOST_RANKS: u64 = 0x0000_FFFF_FF00_0000;
+
+/// Squares of a bishop which an enemy pawn can cut off, with the square of that pawn, from White's side.
+const TRAPPED_BISHOP_SQUARES: [(u32, u32); 4] = [(48, 41), (55, 46), (40, 33), (47, 38)];
+
+/// Rooks with at most this many safe squares can be trapped by their own king.
+const TRAPPED_ROOK_MOBILITY: i32 = 3;
+
+/// Score of both players' piece activity from White's point of view.
+pub fn evaluate_piece_activity(params: &EvalParams, position: &Position) -> (i32, i32) {
+	let (white_mobility, white) = piece_activity(params, position, Player::White);
+	let (black_mobility, black) = piece_activity(params, position, Player::Black);
+
+	return (white_mobility.0 + white.0 - black_mobility.0 - black.0, white_mobility.1 + white.1 - black_mobility.1 - black.1);
+}
+
+/// Safe squares of all of `player`'s pieces except pawns and kings.
+pub fn mobility(position: &Position, player: Player) -> i32 {
+	let board = position.board();
+	let safe = safe_squares(position, player);
+	let mut squares = 0;
+
+	for piece_type in MOBILE_PIECES {
+		let piece = PlayerPiece::new(player, piece_type);
+		let mut pieces = *board.get_piece_bitboard(piece_type) & *board.get_player_bitboard(player);
+
+		while pieces != 0 {
+			let bit_offset = pieces.pop_lsb();
+
+			squares += (board.get_piece_attack_mask(piece, TilePosition::from_bit_offset(bit_offset)) & safe).0.count_ones() as i32;
+		}
+	}
+
+	return squares;
+}
+
+/// Mobility score of `player`'s pieces and the score of the other activity terms.
+pub fn piece_activity(params: &EvalParams, position: &Position, player: Player) -> ((i32, i32), (i32, i32)) {
+	let board = position.board();
+	let enemy = player.opposite();
+	let own_pawns = (board.pawns & *board.get_player_bitboard(player)).0;
+	let enemy_pawns = (board.pawns & *board.get_player_bitboard(enemy)).0;
+	let safe = safe_squares(position, player);
+
+	// The rest is looked at from White's side, like the pawn structure
+	let relative = |bitboard: u64| if player == Player::White { bitboard } else { bitboard.swap_bytes() };
+	let own_king = relative((board.kings & *board.get_player_bitboard(player)).0);
+	let enemy_king = relative((board.kings & *board.get_player_bitboard(enemy)).0);
+	let own_pawn_attacks = relative(pawn_attacks(own_pawns, player));
+	let relative_own_pawns = relative(own_pawns);
+	let relative_enemy_pawns = relative(enemy_pawns);
+	let pawn_count = own_pawns.count_ones() as i32 - BASE_PAWN_COUNT;
+
+	let mut mobility_score = (0, 0);
+	let mut score = (0, 0);
+
+	let add = |score: &mut (i32, i32), weight: (i32, i32), times: i32| {
+		score.0 += weight.0 * times;
+		score.1 += weight.1 * times;
+	};
+
+	for piece_type in MOBILE_PIECES {
+		let piece = PlayerPiece::new(player, piece_type);
+		let mut pieces = *board.get_piece_bitboard(piece_type) & *board.get_player_bitboard(player);
+
+		while pieces != 0 {
+			let bit_offset = pieces.pop_lsb();
+			let attacks = board.get_piece_attack_mask(piece, TilePosition::from_bit_offset(bit_offset)) & safe;
+			let squares = attacks.0.count_ones() as i32;
+
+			add(&mut mobility_score, params.mobility[piece_type as usize], squares - MOBILITY_BASELINE[piece_type as usize]);
+
+			let square = if player == Player::White { bit_offset } else { bit_offset ^ 56 };
+			let file = square % 8;
+			let rank = square / 8;
+
+			// Defended by a pawn and no enemy pawn can ever chase it away
+			let outpost = 1 << square & OUTPOST_RANKS & own_pawn_attacks != 0
+				&& relative_enemy_pawns & adjacent_files(file) & ranks_above(rank) == 0;
+
+			match piece_type {
+				PieceType::Knight => {
+					add(&mut score, params.knight_pawn_count, pawn_count);
+
+					if outpost {
+						add(&mut score, params.knight_outpost, 1);
+					}
+				},
+				PieceType::Bishop => {
+					add(&mut score, params.bishop_pawn_count, pawn_count);
+
+					if outpost {
+						add(&mut score, params.bishop_outpost, 1);
+					}
+
+					if TRAPPED_BISHOP_SQUARES.iter().any(|&(bishop, pawn)| square == bishop && relative_enemy_pawns & 1 << pawn != 0) {
+						add(&mut score, params.trapped_bishop, 1);
+					}
+				},
+				PieceType::Rook => {
+					// Attacking pawns still on their starting rank or keeping the king cut off on the last rank
+					if rank == 6 && (relative_enemy_pawns & Bitboard::generate_rank_mask(6).0 != 0 || enemy_king & Bitboard::generate_rank_mask(7).0 != 0) {
+						add(&mut score, params.rook_on_seventh, 1);
+					}
+
+					if rank == 0 && squares <= TRAPPED_ROOK_MOBILITY && is_trapped_by_king(own_king, file, relative_own_pawns) {
+						add(&mut score, params.trapped_rook, 1);
+					}
+				},
+				_ => {},
+			}
+		}
+	}
+
+	return (mobility_score, score);
+}
+
+/// Whether a rook on `file` of the first rank is shut in between the corner, its own king, which has left its
+/// starting square without castling, and an own pawn on its file.
+fn is_trapped_by_king(own_king: u64, file: u32, own_pawns: u64) -> bool {
+	if own_king & Bitboard::generate_rank_mask(0).0 == 0 {
+		return false;
+	}
+
+	let king_file = own_king.trailing_zeros() % 8;
+
+	if king_file == 4 || own_pawns & Bitboard::generate_column_mask(file).0 == 0 {
+		return false;
+	}
+
+	return (king_file < 4 && file < king_file) || (king_file > 4 && file > king_file);
+}
+
+/// Squares `player`'s pieces can go to without being taken by a pawn.
+fn safe_squares(position: &Position, player: Player) -> Bitboard {
+	let board = position.board();
+	let enemy = player.opposite();
+	let enemy_pawns = (board.pawns & *board.get_player_bitboard(enemy)).0;
+
+	return Bitboard(!pawn_attacks(enemy_pawns, enemy));
+}
+
+/// Squares attacked by `player`'s `pawns`.
+fn pawn_attacks(pawns: u64, player: Player) -> u64 {
+	match player {
+		Player::White => (pawns & !FILE_A) << 7 | (pawns & !FILE_H) << 9,
+		Player::Black => (pawns & !FILE_A) >> 9 | (pawns & !FILE_H) >> 7,
+	}
+}
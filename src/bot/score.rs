@@ -0,0 +1,100 @@
+//! Search score which is either a centipawn evaluation or a mate at some ply from the root.
+
+use std::fmt;
+
+/// Score of mating on the root position itself. Mates further away score one less per ply.
+pub const MATE: i32 = 1000000;
+/// Deepest ply at which a mate can be encoded.
+pub const MAX_MATE_PLY: i32 = 1000;
+/// Scores at or above this in absolute value are mates.
+pub const MATE_THRESHOLD: i32 = MATE - MAX_MATE_PLY;
+
+/// Score from the point of view of the player to move.
+///
+/// Mates are encoded by their ply from the root, so shorter mates score higher and longer defences score higher when being mated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Score(i32);
+
+impl Score {
+	pub const fn new(value: i32) -> Self {
+		Self(value)
+	}
+
+	/// Score of the player to move mating at `ply` from the root.
+	pub const fn mate_in(ply: usize) -> Self {
+		Self(MATE - ply as i32)
+	}
+
+	/// Score of the player to move being mated at `ply` from the root.
+	pub const fn mated_in(ply: usize) -> Self {
+		Self(-MATE + ply as i32)
+	}
+
+	pub const fn value(&self) -> i32 {
+		self.0
+	}
+
+	pub const fn is_mate(&self) -> bool {
+		self.0.abs() >= MATE_THRESHOLD
+	}
+
+	/// Full moves until mate, negative when the player to move is being mated. [`None`] for centipawn scores.
+	pub fn mate_moves(&self) -> Option<i32> {
+		if !self.is_mate() {
+			return None;
+		}
+
+		if self.0 > 0 {
+			Some((MATE - self.0 + 1) / 2)
+		}
+		else {
+			Some(-(MATE + self.0) / 2)
+		}
+	}
+
+	/// Value to store in the transposition table at `ply`. Mates are stored relative to the stored position
+	/// so that the entry stays correct when the position is reached at another ply.
+	pub fn to_transposition(&self, ply: usize) -> i32 {
+		if self.0 >= MATE_THRESHOLD {
+			self.0 + ply as i32
+		}
+		else if self.0 <= -MATE_THRESHOLD {
+			self.0 - ply as i32
+		}
+		else {
+			self.0
+		}
+	}
+
+	/// Read a value stored with [`Score::to_transposition`] back at `ply`.
+	pub fn from_transposition(value: i32, ply: usize) -> Self {
+		if value >= MATE_THRESHOLD {
+			Self(value - ply as i32)
+		}
+		else if value <= -MATE_THRESHOLD {
+			Self(value + ply as i32)
+		}
+		else {
+			Self(value)
+		}
+	}
+
+	/// Score as written in UCI `info` output, like "cp 35" or "mate -3".
+	pub fn uci_string(&self) -> String {
+		match self.mate_moves() {
+			Some(moves) => format!("mate {}", moves),
+			None => format!("cp {}", self.0),
+		}
+	}
+}
+
+impl fmt::Display for Score {
+	/// Formats mates as "M5" or "-M3" and other scores as centipawns.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.mate_moves() {
+			Some(moves) if moves < 0 => write!(f, "-M{}", -moves),
+			Some(moves) => write!(f, "M{}", moves),
+			None => write!(f, "{}", self.0),
+		}
+	}
+}
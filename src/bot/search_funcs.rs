@@ -1,10 +1,10 @@
 //! Negamax, alpha-beta pruning and their multithreading
 
-use std::{collections::HashMap, ptr, sync::{Arc, Mutex}, thread, time::{Duration, Instant}};
+use std::{collections::HashMap, ptr, sync::{atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicUsize, Ordering}, Arc, Mutex, OnceLock}, thread, time::{Duration, Instant}};
 
-use crate::{board::{moove::Move, position::Position}, bot::{evaluation::Evaluation, transposition_table::Transposition}};
+use crate::{board::{moove::Move, position::Position, tile_position::TilePosition}, bot::{evaluation::Evaluation, transposition_table::Transposition}, piece::PieceType, player::Player};
 
-use super::{transposition_table::TranspositionTable, EvaluationFn};
+use super::{move_ordering::{is_capture, is_quiet, order_moves, store_killer}, score::{Score, MATE_THRESHOLD}, search_limits::{PonderHit, SearchLimits}, search_observer::{SearchEvent, SearchInfo, SearchObserver}, search_options::SearchOptions, search_result::{SearchResult, SearchStats}, time_manager::TimeManager, transposition_table::{Bound, TranspositionTable}, evaluator::Evaluator, EvaluationFn};
 
 pub fn negamax_search(position: &Position, evaluation_fn: EvaluationFn, depth: u32) -> (Move, Evaluation) {
 	fn negamax(position: &Position, evaluation_fn: EvaluationFn, depth: u32) -> Evaluation {
@@ -49,8 +49,6 @@ pub fn negamax_search(position: &Position, evaluation_fn: EvaluationFn, depth: u
 			max = score.clone();
 			best_move = Some(m.clone());
 		}
-
-		println!("{}: {:?}", m.debug_string(), score);
 	}
 
 	return (best_move.unwrap(), max);
@@ -400,126 +398,965 @@ pub fn alpha_beta_search_multithreaded(position: &Position, evaluation_fn: fn(&P
 	return (best_eval, best_move.unwrap());
 }
 
-pub fn iterative_deepening(position: &Position, evaluation_fn: fn(&Position) -> i32, search_time: Duration, transposition_table: Arc<TranspositionTable>) -> (i32, Move) {
-	fn alpha_beta(position: Position, evaluation_fn: fn(&Position) -> i32, mut alpha: i32, beta: i32, depth: u32, mut extensions_left: u32, end_time: Instant, transposition_table: *mut TranspositionTable) -> (i32, bool) {
-		if Instant::now() > end_time {
+const MAX_PLY: usize = 128;
+
+const NULL_MOVE_MIN_DEPTH: u32 = 3;
+const NULL_MOVE_REDUCTION: u32 = 2;
+const NULL_MOVE_VERIFICATION_DEPTH: u32 = 8;
+
+const LATE_MOVE_REDUCTION_MIN_DEPTH: u32 = 3;
+const LATE_MOVE_REDUCTION_MIN_MOVE_INDEX: usize = 3;
+
+const REVERSE_FUTILITY_MAX_DEPTH: u32 = 6;
+const REVERSE_FUTILITY_MARGIN: i32 = 120;
+
+const RAZORING_MAX_DEPTH: u32 = 2;
+const RAZORING_MARGIN: (i32, i32) = (300, 200);
+
+/// Futility margins indexed by depth.
+const FUTILITY_MARGINS: [i32; 3] = [0, 200, 400];
+
+const LATE_MOVE_PRUNING_MAX_DEPTH: u32 = 3;
+
+const SINGULAR_EXTENSION_MIN_DEPTH: u32 = 6;
+/// How much shallower than the current depth the transposition table entry may be for a singular extension.
+const SINGULAR_EXTENSION_DEPTH_MARGIN: u32 = 3;
+/// Margin per depth below the transposition table evaluation which the other moves have to stay under.
+const SINGULAR_EXTENSION_MARGIN: i32 = 2;
+
+/// Nodes searched between checks of the clock and the stop signal.
+const STOP_CHECK_INTERVAL: u64 = 1024;
+
+const OBVIOUS_MOVE_MIN_DEPTH: u32 = 4;
+/// How far below the best recapture every other root move has to stay for it to count as obvious.
+const OBVIOUS_MOVE_MARGIN: i32 = 200;
+
+const ASPIRATION_MIN_DEPTH: u32 = 4;
+const ASPIRATION_WINDOW: i32 = 50;
+/// Once the window grows past this the search falls back to the full window.
+const ASPIRATION_MAX_WINDOW: i32 = 1000;
+
+/// Number of quiet moves searched before late move pruning skips the rest.
+fn late_move_pruning_count(depth: u32) -> usize {
+	3 + (depth * depth) as usize
+}
+
+/// Depth reduction for a late move, growing logarithmically with both depth and move index.
+fn late_move_reduction(depth: u32, move_index: usize) -> u32 {
+	static REDUCTIONS: OnceLock<[[u8; 64]; 64]> = OnceLock::new();
+
+	let reductions = REDUCTIONS.get_or_init(|| {
+		let mut table = [[0; 64]; 64];
+
+		for depth in 1..64 {
+			for move_index in 1..64 {
+				table[depth][move_index] = (0.75 + (depth as f64).ln() * (move_index as f64).ln() / 2.25) as u8;
+			}
+		}
+
+		table
+	});
+
+	reductions[(depth as usize).min(63)][move_index.min(63)] as u32
+}
+
+/// Stop conditions shared by every thread of an [`iterative_deepening`] search.
+struct SearchControl {
+	/// Stop signal from outside the search.
+	stop: Arc<AtomicBool>,
+	start_time: Instant,
+	/// Time after which the search is aborted, counted from the ponder hit when pondering.
+	hard_limit: Option<Duration>,
+	ponder: Option<PonderHit>,
+	max_nodes: Option<u64>,
+	/// Nodes searched by all threads, updated every [`STOP_CHECK_INTERVAL`] nodes and when a thread finishes.
+	nodes: AtomicU64,
+	/// Deepest ply reached by any thread.
+	seldepth: AtomicUsize,
+	/// Hashes of the game positions, ending with the root.
+	history: Vec<u64>,
+	/// Varies the evaluation noise between searches.
+	noise_seed: u64,
+}
+
+impl SearchControl {
+	/// Time the clock of the search started, [`None`] while pondering.
+	fn clock_start(&self) -> Option<Instant> {
+		match &self.ponder {
+			Some(ponder_hit) => ponder_hit.time(),
+			None => Some(self.start_time),
+		}
+	}
+
+	fn should_stop(&self) -> bool {
+		self.stop.load(Ordering::Relaxed)
+			|| self.hard_limit.zip(self.clock_start()).is_some_and(|(hard_limit, clock_start)| clock_start.elapsed() > hard_limit)
+			|| self.max_nodes.is_some_and(|max_nodes| self.nodes.load(Ordering::Relaxed) >= max_nodes)
+	}
+}
+
+/// State of a single search thread in [`iterative_deepening`].
+struct SearchContext {
+	evaluator: Arc<dyn Evaluator>,
+	control: Arc<SearchControl>,
+	transposition_table: *mut TranspositionTable,
+	options: SearchOptions,
+	killers: Vec<[Option<Move>; 2]>,
+	/// Square where the move made at each ply captured, used for recapture extensions.
+	captured_on: Vec<Option<TilePosition>>,
+	root_depth: u32,
+	/// Hashes of the game positions followed by the positions on the current search path, indexed by ply after the root.
+	path: Vec<u64>,
+	root_index: usize,
+	nodes: u64,
+	seldepth: usize,
+	stopped: bool,
+}
+
+impl SearchContext {
+	fn new(evaluator: Arc<dyn Evaluator>, control: Arc<SearchControl>, transposition_table: *mut TranspositionTable, options: SearchOptions, root_depth: u32) -> Self {
+		let root_index = control.history.len() - 1;
+
+		let mut path = control.history.clone();
+		path.resize(root_index + MAX_PLY + 1, 0);
+
+		Self {
+			evaluator,
+			control,
+			transposition_table,
+			options,
+			killers: vec![[None, None]; MAX_PLY],
+			captured_on: vec![None; MAX_PLY],
+			root_depth,
+			path,
+			root_index,
+			nodes: 0,
+			seldepth: 0,
+			stopped: false,
+		}
+	}
+
+	/// Return `true` once the search has to stop. The stop conditions are only checked every [`STOP_CHECK_INTERVAL`] nodes.
+	fn should_stop(&mut self) -> bool {
+		if !self.stopped && self.nodes % STOP_CHECK_INTERVAL == 0 {
+			self.control.nodes.fetch_add(STOP_CHECK_INTERVAL, Ordering::Relaxed);
+			self.stopped = self.control.should_stop();
+		}
+
+		self.stopped
+	}
+
+	/// Static evaluation of `position` with [`SearchOptions::eval_noise`] added.
+	fn evaluate(&self, position: &Position) -> i32 {
+		let eval = self.evaluator.evaluate(position);
+
+		if self.options.eval_noise <= 0 {
+			return eval;
+		}
+
+		let mixed = (position.hash().value() ^ self.control.noise_seed).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+		let range = 2 * self.options.eval_noise as u64 + 1;
+
+		return eval + ((mixed >> 32) % range) as i32 - self.options.eval_noise;
+	}
+
+	/// Count a node at `ply`.
+	fn visit(&mut self, ply: usize) {
+		self.nodes += 1;
+		self.seldepth = self.seldepth.max(ply);
+	}
+
+	/// Record `position` on the search path at `ply` and return `true` if it is drawn by repetition or the fifty-move rule.
+	fn is_draw(&mut self, position: &Position, ply: usize) -> bool {
+		let index = self.root_index + ply;
+		let hash = position.hash().value();
+
+		self.path[index] = hash;
+
+		if position.halfmove_clock() >= 100 {
+			// Checkmate on the last move still counts
+			return !position.is_in_check(position.current_player()) || !position.get_all_legal_moves().is_empty();
+		}
+
+		// Only positions since the last capture or pawn move with the same player to move can repeat
+		let earliest = index.saturating_sub(position.halfmove_clock() as usize);
+		let mut repetitions = 0;
+		let mut i = index;
+
+		while i >= earliest + 2 {
+			i -= 2;
+
+			if self.path[i] != hash {
+				continue;
+			}
+
+			// Repeating inside the search could be done again, so it is a draw already. Game positions have to repeat twice.
+			if i >= self.root_index {
+				return true;
+			}
+
+			repetitions += 1;
+
+			if repetitions == 2 {
+				return true;
+			}
+		}
+
+		false
+	}
+
+	/// Score of a draw at `ply`. Contempt makes draws bad for the player to move at the root.
+	fn draw_score(&self, ply: usize) -> i32 {
+		if ply % 2 == 0 { -self.options.contempt } else { self.options.contempt }
+	}
+
+	/// Return `true` if a line which has been extended `extensions` times may be extended again at `ply`, see
+	/// [`can_extend`].
+	fn can_extend(&self, ply: usize, extensions: u32) -> bool {
+		can_extend(self.root_depth, ply, extensions)
+	}
+}
+
+impl Drop for SearchContext {
+	fn drop(&mut self) {
+		// Nodes since the last stop check haven't been counted yet
+		self.control.nodes.fetch_add(self.nodes % STOP_CHECK_INTERVAL, Ordering::Relaxed);
+		self.control.seldepth.fetch_max(self.seldepth, Ordering::Relaxed);
+	}
+}
+
+/// Return `true` if a line which has been extended `extensions` times may be extended again at `ply` in an iteration
+/// with `root_depth`. A line is extended fewer than `root_depth / 2` times, so never at depth 1, and not beyond twice the
+/// root depth.
+///
+/// Both limits grow with the root depth so that extensions can't make an iteration explode.
+pub fn can_extend(root_depth: u32, ply: usize, extensions: u32) -> bool {
+	extensions < root_depth / 2 && ply < 2 * root_depth as usize
+}
+
+/// Square the move leading to the last position of `history` captured on, if it was a capture. A recapture there is
+/// often the only reasonable move.
+pub fn last_capture_square(history: &[Position]) -> Option<TilePosition> {
+	let [.., previous, position] = history else {
+		return None;
+	};
+
+	let hash = position.hash().value();
+
+	let moove = previous.get_all_legal_moves().into_iter().find(|m| {
+		let mut moved = previous.clone();
+		moved.make_move(m.clone());
+
+		moved.hash().value() == hash
+	})?;
+
+	if is_capture(previous, &moove) { Some(moove.to_position()) } else { None }
+}
+
+/// Follow the best moves stored in the transposition table from `position` after `best_move`.
+fn principal_variation(position: &Position, best_move: &Move, transposition_table: &TranspositionTable, max_length: usize) -> Vec<Move> {
+	let mut pv = vec![best_move.clone()];
+
+	let mut position = position.clone();
+	position.make_move(best_move.clone());
+
+	let table = ptr::from_ref(transposition_table) as *mut TranspositionTable;
+
+	while pv.len() < max_length {
+		let hash = position.hash().value();
+		let tp = unsafe { (*table).get(hash) };
+
+		if !tp.hash_matches(hash) {
+			break;
+		}
+
+		// Entries can be overwritten by other positions, so the move has to be checked
+		let Some(m) = tp.best_move(position.current_player()).filter(|m| position.get_all_legal_moves().contains(m)) else {
+			break;
+		};
+
+		position.make_move(m.clone());
+		pv.push(m);
+	}
+
+	pv
+}
+
+/// Return `true` if the move is a pawn advancing to the 7th rank, where nothing can stop it from promoting next move.
+fn is_pawn_to_seventh_rank(position: &Position, moove: &Move) -> bool {
+	let Move::Basic(basic_move) = moove else {
+		return false;
+	};
+
+	if position.get_piece(basic_move.from_position()).map(|p| p.piece()) != Some(PieceType::Pawn) {
+		return false;
+	}
+
+	let seventh_rank = match position.current_player() {
+		Player::White => 6,
+		Player::Black => 1,
+	};
+
+	basic_move.to_position().rank() == seventh_rank
+}
+
+/// Move at the root of [`iterative_deepening`] with its results from the latest iteration.
+struct RootMove {
+	moove: Move,
+	score: i32,
+	/// Moves which failed low against a better move only have an upper bound, good for ordering but not as a line.
+	bound: Bound,
+	nodes: u64,
+}
+
+/// Search captures and promotions until the position is quiet, so that the evaluation isn't taken in the middle of an exchange.
+///
+/// All moves are searched while in check.
+fn quiescence_search(context: &mut SearchContext, position: &Position, mut alpha: i32, beta: i32, ply: usize) -> (i32, bool) {
+	context.visit(ply);
+
+	if context.should_stop() {
+		return (0, false);
+	}
+
+	let in_check = position.is_in_check(position.current_player());
+
+	if !in_check {
+		let stand_pat = context.evaluate(position);
+
+		if stand_pat >= beta || ply >= MAX_PLY {
+			return (stand_pat, true);
+		}
+
+		alpha = alpha.max(stand_pat);
+	}
+
+	let mut legal_moves = position.get_all_legal_moves();
+
+	if legal_moves.len() == 0 {
+		if in_check {
+			return (Score::mated_in(ply).value(), true);
+		}
+
+		return (context.draw_score(ply), true);
+	}
+
+	if !in_check {
+		legal_moves.retain(|m| !is_quiet(position, m));
+	}
+
+	order_moves(position, &mut legal_moves, None, &[None, None]);
+
+	for m in legal_moves {
+		let mut moved_position = position.clone();
+		moved_position.make_move(m);
+
+		let (mut eval, complete_search) = quiescence_search(context, &moved_position, -beta, -alpha, ply + 1);
+		eval = -eval;
+
+		if !complete_search {
+			return (0, complete_search);
+		}
+
+		if eval >= beta {
+			return (eval, true);
+		}
+
+		alpha = alpha.max(eval);
+	}
+
+	(alpha, true)
+}
+
+/// Search `position`, reached after the game positions with the hashes in `history`, with iterative deepening until
+/// one of the `limits` is hit or `stop` is set. `last_capture` is the [`last_capture_square`] of the game, where
+/// recapturing can end the search early.
+///
+/// Every finished iteration is reported to `observer`. Returns `None` without searching if the game is already over
+/// at `position`, as there is no move to play.
+pub fn iterative_deepening(position: &Position, history: &[u64], last_capture: Option<TilePosition>, evaluator: Arc<dyn Evaluator>, limits: &SearchLimits, stop: Arc<AtomicBool>, observer: &dyn SearchObserver, transposition_table: Arc<TranspositionTable>, options: &SearchOptions) -> Option<SearchResult> {
+	fn alpha_beta(context: &mut SearchContext, position: Position, mut alpha: i32, beta: i32, depth: u32, ply: usize, extensions: u32, allow_null_move: bool) -> (i32, bool) {
+		context.visit(ply);
+
+		if context.should_stop() {
 			return (0, false);
 		}
+
+		if context.is_draw(&position, ply) {
+			return (context.draw_score(ply), true);
+		}
 		
-		if depth == 0 {
-			return (evaluation_fn(&position), true);
+		if depth == 0 || ply >= MAX_PLY {
+			if context.options.quiescence_search {
+				return quiescence_search(context, &position, alpha, beta, ply);
+			}
+
+			return (context.evaluate(&position), true);
 		};
 
+		let hash = position.hash().value();
+		let mut transposition_move = None;
+		let mut transposition_entry = None;
+
 		unsafe {
-			let tp = (*transposition_table).get(position.hash().value());
+			let tp = (*context.transposition_table).get(hash);
+
+			if tp.hash_matches(hash) {
+				transposition_move = tp.best_move(position.current_player());
+				let eval = Score::from_transposition(tp.evaluation(), ply).value();
+				transposition_entry = Some((tp.depth(), eval, tp.bound()));
 
-			if tp.hash_matches(position.hash().value()) {
 				if tp.depth() >= depth {
-					let eval = tp.evaluation();
-					if eval.abs() < 1000000 {
-						return (eval, true);
+					match tp.bound() {
+						Bound::Exact => return (eval, true),
+						Bound::Lower if eval >= beta => return (eval, true),
+						Bound::Upper if eval <= alpha => return (eval, true),
+						_ => {}
 					}
 				}
 			}
 		}
 
-		let legal_moves = position.get_all_legal_moves();
+		let in_check = position.is_in_check(position.current_player());
+
+		// Static evaluation is meaningless while in check, so static pruning is skipped then.
+		let static_eval = if in_check { None } else { Some(context.evaluate(&position)) };
+
+		if let Some(static_eval) = static_eval {
+			// Reverse futility pruning: static evaluation is so far above beta that a quiet move won't drop below it.
+			if context.options.reverse_futility_pruning
+				&& depth <= REVERSE_FUTILITY_MAX_DEPTH
+				&& beta.abs() < MATE_THRESHOLD
+				&& static_eval - REVERSE_FUTILITY_MARGIN * depth as i32 >= beta
+			{
+				return (static_eval - REVERSE_FUTILITY_MARGIN * depth as i32, true);
+			}
+
+			// Razoring: static evaluation is far below alpha, verify with quiescence search whether any capture saves the position.
+			if context.options.razoring
+				&& depth <= RAZORING_MAX_DEPTH
+				&& alpha.abs() < MATE_THRESHOLD
+				&& static_eval + RAZORING_MARGIN.0 + RAZORING_MARGIN.1 * depth as i32 <= alpha
+			{
+				let (razor_eval, complete_search) = quiescence_search(context, &position, alpha, beta, ply);
+
+				if !complete_search {
+					return (0, complete_search);
+				}
+
+				if razor_eval <= alpha {
+					return (razor_eval, true);
+				}
+			}
+		}
+
+		// Null move pruning. Skipped in pawn-only endgames where zugzwang is common.
+		if context.options.null_move_pruning
+			&& allow_null_move
+			&& !in_check
+			&& depth >= NULL_MOVE_MIN_DEPTH
+			&& beta.abs() < MATE_THRESHOLD
+			&& position.has_non_pawn_material(position.current_player())
+			&& static_eval.is_some_and(|e| e >= beta)
+		{
+			let reduction = NULL_MOVE_REDUCTION + depth / 4;
+
+			let mut null_position = position.clone();
+			null_position.make_null_move();
+			context.captured_on[ply] = None;
+
+			let (mut null_eval, complete_search) = alpha_beta(context, null_position, -beta, -beta + 1, depth.saturating_sub(1 + reduction), ply + 1, extensions, false);
+			null_eval = -null_eval;
+
+			if !complete_search {
+				return (0, complete_search);
+			}
+
+			if null_eval >= beta {
+				if depth < NULL_MOVE_VERIFICATION_DEPTH {
+					return (beta, true);
+				}
+
+				// Verify at high depth with a reduced search of the same position without null moves
+				let (verified_eval, complete_search) = alpha_beta(context, position.clone(), beta - 1, beta, depth - reduction, ply, extensions, false);
+
+				if !complete_search {
+					return (0, complete_search);
+				}
+
+				if verified_eval >= beta {
+					return (beta, true);
+				}
+			}
+		}
+
+		let mut legal_moves = position.get_all_legal_moves();
 
 		if legal_moves.len() == 0 {
-			if position.is_in_check(position.current_player()) {
-				return (-1000000 * (depth as i32 + 1), true);
+			if in_check {
+				return (Score::mated_in(ply).value(), true);
 			};
 			
-			return (0, true);
+			return (context.draw_score(ply), true);
 		};
 
-		for m in legal_moves {
-			let mut new_depth = depth;
+		order_moves(&position, &mut legal_moves, transposition_move.as_ref(), &context.killers[ply]);
+
+		let original_alpha = alpha;
+		let mut best_move = None;
+
+		// Quiet moves can't raise a hopeless static evaluation above alpha near the leaves.
+		let futility_pruning = context.options.futility_pruning
+			&& depth < FUTILITY_MARGINS.len() as u32
+			&& alpha.abs() < MATE_THRESHOLD
+			&& static_eval.is_some_and(|e| e + FUTILITY_MARGINS[depth as usize] <= alpha);
+
+		let late_move_pruning = context.options.late_move_pruning
+			&& depth <= LATE_MOVE_PRUNING_MAX_DEPTH
+			&& !in_check
+			&& alpha.abs() < MATE_THRESHOLD;
+
+		let mut quiet_moves_searched = 0;
+
+		// Singular extension: the transposition table move failed high before and every other move falls clearly short of it.
+		let singular_beta = match transposition_entry {
+			Some((tp_depth, tp_eval, tp_bound)) if context.options.singular_extensions
+				&& depth >= SINGULAR_EXTENSION_MIN_DEPTH
+				&& tp_depth + SINGULAR_EXTENSION_DEPTH_MARGIN >= depth
+				&& tp_bound != Bound::Upper
+				&& tp_eval.abs() < MATE_THRESHOLD
+				&& context.can_extend(ply, extensions) => Some(tp_eval - SINGULAR_EXTENSION_MARGIN * depth as i32),
+			_ => None,
+		};
+
+		for (move_index, m) in legal_moves.into_iter().enumerate() {
+			let is_capture_move = is_capture(&position, &m);
+			let is_quiet_move = is_quiet(&position, &m);
+			let is_killer_move = context.killers[ply].contains(&Some(m.clone()));
+
+			let mut moved_position = position.clone();
+			moved_position.make_move(m.clone());
+
+			let gives_check = moved_position.is_in_check(moved_position.current_player());
+
+			let mut extension = 0;
+
+			if context.can_extend(ply, extensions) {
+				let is_recapture = is_capture_move && context.captured_on[ply - 1] == Some(m.to_position());
+
+				if (context.options.check_extensions && gives_check)
+					|| (context.options.recapture_extensions && is_recapture)
+					|| (context.options.passed_pawn_extensions && is_pawn_to_seventh_rank(&position, &m))
+					|| (is_capture_move && depth == 1)
+				{
+					extension = 1;
+				}
+				else if let Some(singular_beta) = singular_beta.filter(|_| transposition_move.as_ref() == Some(&m)) {
+					let (singular, complete_search) = is_singular(context, &position, &m, singular_beta, depth, ply, extensions);
+
+					if !complete_search {
+						return (0, complete_search);
+					}
 
-			if extensions_left > 0 {
-				if position.get_piece(m.to_position()).is_some() {
-					if depth == 1 {
-						new_depth += 1;
-						extensions_left -= 1;
+					if singular {
+						extension = 1;
 					}
 				}
 			}
 
-			let mut moved_position = position.clone();
-			moved_position.make_move(m);
+			let new_depth = depth + extension;
+			context.captured_on[ply] = if is_capture_move { Some(m.to_position()) } else { None };
 
-			let (mut eval, complete_search) = alpha_beta(moved_position, evaluation_fn, -beta, -alpha, new_depth - 1, extensions_left, end_time, transposition_table);
-			eval = -eval;
+			if is_quiet_move && !gives_check && move_index > 0 {
+				if futility_pruning {
+					continue;
+				}
 
-			if !complete_search {
-				return (0, complete_search);
+				if late_move_pruning && quiet_moves_searched >= late_move_pruning_count(depth) {
+					continue;
+				}
 			}
 
-			let hash = position.hash().value();
-			// Transposition::new(hash, depth - 1, eval)
-			unsafe { *(*transposition_table).get(hash) = Transposition::new(hash, depth - 1, eval) };
+			if is_quiet_move {
+				quiet_moves_searched += 1;
+			}
+
+			let reduction = if context.options.late_move_reductions
+				&& depth >= LATE_MOVE_REDUCTION_MIN_DEPTH
+				&& move_index >= LATE_MOVE_REDUCTION_MIN_MOVE_INDEX
+				&& is_quiet_move
+				&& !is_killer_move
+				&& !in_check
+				&& !gives_check
+			{
+				late_move_reduction(depth, move_index)
+			}
+			else {
+				0
+			};
+
+			let mut eval = alpha + 1;
+
+			if reduction > 0 {
+				let reduced_depth = (new_depth - 1).saturating_sub(reduction).max(1);
+
+				let (reduced_eval, complete_search) = alpha_beta(context, moved_position.clone(), -alpha - 1, -alpha, reduced_depth, ply + 1, extensions + extension, true);
+
+				if !complete_search {
+					return (0, complete_search);
+				}
+
+				eval = -reduced_eval;
+			}
+
+			// Reduced search failed high or the move wasn't reduced, search with full depth
+			if eval > alpha {
+				let (full_eval, complete_search) = alpha_beta(context, moved_position, -beta, -alpha, new_depth - 1, ply + 1, extensions + extension, true);
+
+				if !complete_search {
+					return (0, complete_search);
+				}
+
+				eval = -full_eval;
+			}
 
 			if eval >= beta {
-				return (eval, complete_search);
+				if is_quiet_move {
+					store_killer(&mut context.killers[ply], &m);
+				}
+
+				unsafe { *(*context.transposition_table).get(hash) = Transposition::new(hash, depth, Score::new(eval).to_transposition(ply), Bound::Lower, Some(&m)) };
+
+				return (eval, true);
 			}
 
-			alpha = eval.max(alpha);
+			if eval > alpha {
+				alpha = eval;
+				best_move = Some(m);
+			}
 		};
 
+		let bound = if alpha > original_alpha { Bound::Exact } else { Bound::Upper };
+
+		unsafe { *(*context.transposition_table).get(hash) = Transposition::new(hash, depth, Score::new(alpha).to_transposition(ply), bound, best_move.as_ref()) };
+
 		return (alpha, true)
 	}
 
-	let end_time = Instant::now() + search_time;
+	/// Search every move except `singular_move` with a reduced null window at `singular_beta`. Returns whether all of
+	/// them failed low, meaning `singular_move` is the only good move, and whether the search finished in time.
+	fn is_singular(context: &mut SearchContext, position: &Position, singular_move: &Move, singular_beta: i32, depth: u32, ply: usize, extensions: u32) -> (bool, bool) {
+		for m in position.get_all_legal_moves() {
+			if m == *singular_move {
+				continue;
+			}
 
-	let mut depth = 0;
+			let mut moved_position = position.clone();
+			moved_position.make_move(m.clone());
+			context.captured_on[ply] = if is_capture(position, &m) { Some(m.to_position()) } else { None };
 
-	let legal_moves = position.get_all_legal_moves();
-	let mut evaled_moves: Vec<(i32, Move)> = legal_moves.iter().map(|e| (0, e.clone())).collect();
-	let mut finished_moves = evaled_moves.clone();
+			let (eval, complete_search) = alpha_beta(context, moved_position, -singular_beta, -singular_beta + 1, (depth - 1) / 2, ply + 1, extensions, true);
 
-	while Instant::now() < end_time {
-		let alpha = i32::MIN + 1;
-		let beta = i32::MAX;
+			if !complete_search {
+				return (false, complete_search);
+			}
 
-		let mut threads = Vec::new();
+			if -eval >= singular_beta {
+				return (false, true);
+			}
+		}
 
-		let tp_ptr = ptr::from_ref(transposition_table.as_ref()) as *mut TranspositionTable;
+		return (true, true);
+	}
 
-		for m in &legal_moves {
-			let mut moved_position = position.clone();
-			moved_position.make_move(m.clone());
+	/// Search a single root move, either with the full window or, for moves after the first, with a null window
+	/// around the best score found so far and a re-search on fail-high. Returns the score, how it relates to the real
+	/// value, the node count and whether the search finished in time.
+	fn search_root_move(context: &mut SearchContext, moved_position: Position, shared_alpha: &AtomicI32, beta: i32, depth: u32, full_window: bool) -> (i32, Bound, u64, bool) {
+		let start_nodes = context.nodes;
+		let mut alpha = shared_alpha.load(Ordering::Relaxed);
+
+		let (mut eval, mut complete_search) = if full_window {
+			alpha_beta(context, moved_position.clone(), -beta, -alpha, depth, 1, 0, true)
+		}
+		else {
+			alpha_beta(context, moved_position.clone(), -alpha - 1, -alpha, depth, 1, 0, true)
+		};
+		eval = -eval;
+
+		if complete_search && !full_window && eval > alpha && eval < beta {
+			// Another move may have raised alpha in the meantime
+			alpha = shared_alpha.load(Ordering::Relaxed).min(eval - 1);
+
+			(eval, complete_search) = alpha_beta(context, moved_position, -beta, -alpha, depth, 1, 0, true);
+			eval = -eval;
+		}
+
+		if complete_search {
+			shared_alpha.fetch_max(eval.min(beta), Ordering::Relaxed);
+		}
+
+		let bound = if eval <= alpha { Bound::Upper } else if eval >= beta { Bound::Lower } else { Bound::Exact };
+
+		return (eval, bound, context.nodes - start_nodes, complete_search);
+	}
 
+	/// Square captured on by a root move, seeding the recapture extension at the first ply.
+	fn root_capture_square(position: &Position, moove: &Move) -> Option<TilePosition> {
+		if is_capture(position, moove) { Some(moove.to_position()) } else { None }
+	}
+
+	/// Search every root move with the window `alpha..beta`. The first move is searched alone, the rest by a thread
+	/// per core taking the next unsearched move and sharing the best score so far. Updates the scores and node counts
+	/// of `root_moves` and returns the best move found with its score, along with whether every move finished in time.
+	fn search_root(position: &Position, root_moves: &mut [RootMove], alpha: i32, beta: i32, depth: u32, evaluator: &Arc<dyn Evaluator>, control: &Arc<SearchControl>, tp_ptr: *mut TranspositionTable, options: &SearchOptions) -> (Option<(i32, Move)>, bool) {
+		let shared_alpha = AtomicI32::new(alpha);
+
+		let mut first_position = position.clone();
+		first_position.make_move(root_moves[0].moove.clone());
+
+		let mut context = SearchContext::new(evaluator.clone(), control.clone(), tp_ptr, options.clone(), depth);
+		context.captured_on[0] = root_capture_square(position, &root_moves[0].moove);
+		let first_result = search_root_move(&mut context, first_position, &shared_alpha, beta, depth, true);
+
+		let mut results = vec![None; root_moves.len()];
+		results[0] = Some(first_result);
+
+		if first_result.3 && root_moves.len() > 1 {
+			let next_move = AtomicUsize::new(1);
+			let thread_count = options.threads.clamp(1, root_moves.len() - 1);
 			let tp = tp_ptr as usize;
-			let m = m.clone();
+			let moves: &[RootMove] = root_moves;
 
-			threads.push(thread::spawn(move || {
-				let tp_ptr = tp as *mut TranspositionTable;
+			let thread_results = thread::scope(|scope| {
+				let threads: Vec<_> = (0..thread_count).map(|_| scope.spawn(|| {
+					let tp_ptr = tp as *mut TranspositionTable;
+					let mut context = SearchContext::new(evaluator.clone(), control.clone(), tp_ptr, options.clone(), depth);
+					let mut thread_results = Vec::new();
 
-				let (mut eval, complete_search) = alpha_beta(moved_position, evaluation_fn, -beta, -alpha, depth, 4, end_time, tp_ptr);
-				eval = -eval;
+					loop {
+						let index = next_move.fetch_add(1, Ordering::Relaxed);
 
-				return (eval, m.clone(), complete_search);
-			}));
-		};
+						let Some(root_move) = moves.get(index) else {
+							break;
+						};
+
+						let mut moved_position = position.clone();
+						moved_position.make_move(root_move.moove.clone());
+						context.captured_on[0] = root_capture_square(position, &root_move.moove);
+
+						let result = search_root_move(&mut context, moved_position, &shared_alpha, beta, depth, false);
+						thread_results.push((index, result));
+
+						if !result.3 {
+							break;
+						}
+					}
+
+					thread_results
+				})).collect();
 
+				threads.into_iter().map(|t| t.join().unwrap()).collect::<Vec<_>>()
+			});
+
+			for (index, result) in thread_results.into_iter().flatten() {
+				results[index] = Some(result);
+			}
+		}
+
+		let mut best: Option<(i32, Move)> = None;
 		let mut completed = true;
 
-		for (i, t) in threads.into_iter().enumerate() {
-			let (eval, m, complete_search) = t.join().unwrap();
+		for (root_move, result) in root_moves.iter_mut().zip(results) {
+			let Some((eval, bound, nodes, true)) = result else {
+				completed = false;
+				continue;
+			};
+
+			root_move.score = eval;
+			root_move.bound = bound;
+			root_move.nodes = nodes;
+
+			// Later moves only take over with a strictly better score, and not with a fail-low result whose score is
+			// only a bound
+			if best.as_ref().map_or(true, |(best_eval, _)| eval > *best_eval && bound != Bound::Upper) {
+				best = Some((eval, root_move.moove.clone()));
+			}
+		}
+
+		return (best, completed);
+	}
+
+	/// Search `root_moves` with an aspiration window around `previous_score`, widening the window until the score
+	/// falls inside it. Returns the best move with its score and whether the search finished in time. An unfinished
+	/// search only returns a move if it beat the window.
+	fn search_with_aspiration(position: &Position, root_moves: &mut [RootMove], previous_score: Option<i32>, depth: u32, evaluator: &Arc<dyn Evaluator>, control: &Arc<SearchControl>, tp_ptr: *mut TranspositionTable, options: &SearchOptions) -> (Option<(i32, Move)>, bool) {
+		let mut window = ASPIRATION_WINDOW;
+
+		let (mut alpha, mut beta) = match previous_score {
+			Some(score) if options.aspiration_windows && depth >= ASPIRATION_MIN_DEPTH && !Score::new(score).is_mate() => (score - window, score + window),
+			_ => (i32::MIN + 1, i32::MAX),
+		};
+
+		loop {
+			let (result, complete_search) = search_root(position, root_moves, alpha, beta, depth, evaluator, control, tp_ptr, options);
 
 			if !complete_search {
-				completed = false;
+				// The first move finished, so any move which beat the window is better than the previous best
+				return (result.filter(|(eval, _)| *eval > alpha), false);
 			}
 
-			evaled_moves[i] = (eval, m.clone());
+			let (eval, m) = result.unwrap();
+
+			window *= 2;
+
+			if eval <= alpha && alpha > i32::MIN + 1 {
+				alpha = if window > ASPIRATION_MAX_WINDOW { i32::MIN + 1 } else { alpha.saturating_sub(window).max(i32::MIN + 1) };
+				continue;
+			}
+
+			if eval >= beta && beta < i32::MAX {
+				beta = if window > ASPIRATION_MAX_WINDOW { i32::MAX } else { beta.saturating_add(window) };
+				continue;
+			}
+
+			return (Some((eval, m)), true);
 		}
+	}
 
-		if !completed {
+	/// Return `true` if every root move after the first stays clearly below `best_score` in a reduced search,
+	/// like after the opponent captured and there is only one sensible recapture.
+	fn is_obvious_move(position: &Position, root_moves: &[RootMove], best_score: i32, depth: u32, evaluator: &Arc<dyn Evaluator>, control: &Arc<SearchControl>, tp_ptr: *mut TranspositionTable, options: &SearchOptions) -> bool {
+		let obvious_beta = best_score - OBVIOUS_MOVE_MARGIN;
+
+		let mut context = SearchContext::new(evaluator.clone(), control.clone(), tp_ptr, options.clone(), depth / 2);
+
+		for root_move in root_moves.iter().skip(1) {
+			let mut moved_position = position.clone();
+			moved_position.make_move(root_move.moove.clone());
+
+			let (eval, complete_search) = alpha_beta(&mut context, moved_position, -obvious_beta, -obvious_beta + 1, depth / 2, 1, 0, true);
+
+			if !complete_search || -eval >= obvious_beta {
+				return false;
+			}
+		}
+
+		return true;
+	}
+
+	let mut legal_moves = position.get_all_legal_moves();
+
+	if legal_moves.is_empty() {
+		return None;
+	}
+
+	let start_time = Instant::now();
+	let mut time_manager = limits.active_time_control().map(TimeManager::new);
+
+	let control = Arc::new(SearchControl {
+		stop,
+		start_time,
+		hard_limit: time_manager.as_ref().map(|t| t.hard_limit()),
+		ponder: limits.ponder.clone(),
+		max_nodes: limits.nodes,
+		nodes: AtomicU64::new(0),
+		seldepth: AtomicUsize::new(0),
+		history: history.iter().copied().chain([position.hash().value()]).collect(),
+		noise_seed: rand::random(),
+	});
+
+	let max_depth = limits.max_depth();
+	let tp_ptr = ptr::from_ref(transposition_table.as_ref()) as *mut TranspositionTable;
+
+	let mut depth = 0;
+	let mut completed_depth = 0;
+
+	order_moves(position, &mut legal_moves, None, &[None, None]);
+
+	let mut root_moves: Vec<RootMove> = legal_moves.into_iter().map(|m| RootMove { moove: m, score: i32::MIN + 1, bound: Bound::Upper, nodes: 0 }).collect();
+	let multi_pv = options.multi_pv.clamp(1, root_moves.len());
+	let mut best = (0, root_moves[0].moove.clone());
+	// Principal variation of the best line of the last completed iteration
+	let mut best_pv = Vec::new();
+	let mut previous_scores = vec![None; multi_pv];
+
+	loop {
+		let previous_best_move = root_moves[0].moove.clone();
+		let mut lines = Vec::new();
+
+		// Each line searches the moves which aren't part of an earlier line
+		for pv_index in 0..multi_pv {
+			let (result, complete_search) = search_with_aspiration(position, &mut root_moves[pv_index..], previous_scores[pv_index], depth, &evaluator, &control, tp_ptr, options);
+
+			if !complete_search {
+				if pv_index == 0 {
+					if let Some(result) = result {
+						best = result;
+					}
+				}
+
+				break;
+			}
+
+			let (eval, m) = result.unwrap();
+
+			// Move the line's move in front of the moves left for the next lines
+			let index = root_moves.iter().position(|r| r.moove == m).unwrap();
+			root_moves[pv_index..=index].rotate_right(1);
+
+			lines.push((eval, m));
+		}
+
+		if let Some(line) = lines.first() {
+			best = line.clone();
+		}
+
+		if lines.len() < multi_pv {
+			break;
+		}
+
+		// A later line can come out ahead when the searches don't agree exactly
+		root_moves[..multi_pv].sort_by(|a, b| b.score.cmp(&a.score));
+		let lines: Vec<(i32, Move)> = root_moves[..multi_pv].iter().map(|r| (r.score, r.moove.clone())).collect();
+		best = lines[0].clone();
+
+		let best_move_changed = previous_best_move != best.1;
+		completed_depth = depth + 1;
+		previous_scores = lines.iter().map(|(eval, _)| Some(*eval)).collect();
+
+		let nodes = control.nodes.load(Ordering::Relaxed);
+		let time = start_time.elapsed();
+		let seldepth = control.seldepth.load(Ordering::Relaxed) as u32;
+		let hashfull = transposition_table.hashfull();
+
+		for (pv_index, (eval, m)) in lines.iter().enumerate() {
+			let pv = principal_variation(position, m, &transposition_table, depth as usize + 1);
+
+			if pv_index == 0 {
+				best_pv = pv.clone();
+			}
+
+			observer.notify(SearchEvent::Iteration(SearchInfo {
+				multi_pv: pv_index as u32 + 1,
+				depth: depth + 1,
+				seldepth,
+				score: Score::new(*eval),
+				nodes,
+				nps: (nodes as f64 / time.as_secs_f64().max(0.001)) as u64,
+				hashfull,
+				time,
+				pv,
+			}));
+		}
+
+		// Lines first, then moves with exact scores and the moves which needed the most work to refute
+		root_moves[multi_pv..].sort_by(|a, b| (a.bound != Bound::Exact).cmp(&(b.bound != Bound::Exact)).then(b.score.cmp(&a.score)).then(b.nodes.cmp(&a.nodes)));
+
+		// Searched depth in plies is one more than the depth below the root moves
+		if max_depth.is_some_and(|max_depth| depth + 1 >= max_depth) {
 			break;
 		}
-		
-		evaled_moves.sort_by(|a, b| b.0.cmp(&a.0));
-		finished_moves = evaled_moves.clone();
 
 		if depth < 2 {
 			depth += 1;
@@ -528,28 +1365,68 @@ pub fn iterative_deepening(position: &Position, evaluation_fn: fn(&Position) ->
 			depth += 2;
 		}
 
-		if finished_moves[0].0.abs() > 100000 {
+		if let Some(max_depth) = max_depth {
+			depth = depth.min(max_depth - 1);
+		}
+
+		if root_moves.len() == 1 || Score::new(best.0).is_mate() {
 			break;
 		}
+
+		if let Some(time_manager) = &mut time_manager {
+			// Keep pondering until the ponder hit, then take the time from there
+			let Some(clock_start) = control.clock_start() else {
+				time_manager.iteration_finished(best_move_changed, best.0);
+				continue;
+			};
+
+			time_manager.restart_at(clock_start);
+
+			if time_manager.can_stop_early()
+				&& depth >= OBVIOUS_MOVE_MIN_DEPTH
+				&& last_capture == Some(best.1.to_position())
+				&& is_capture(position, &best.1)
+				&& is_obvious_move(position, &root_moves, best.0, depth, &evaluator, &control, tp_ptr, options)
+			{
+				time_manager.set_obvious_move();
+			}
+
+			if time_manager.iteration_finished(best_move_changed, best.0) {
+				break;
+			}
+		}
+
 	};
 
-	for (eval, m) in finished_moves.iter() {
-		println!("{} | {}", m.debug_string(), eval);
-	}
+	let stats = SearchStats {
+		depth: completed_depth,
+		seldepth: control.seldepth.load(Ordering::Relaxed) as u32,
+		nodes: control.nodes.load(Ordering::Relaxed),
+		time: start_time.elapsed(),
+	};
 
-	println!("Depth: {}", depth);
+	// The line is unknown if an unfinished iteration found a better move
+	if best_pv.first() != Some(&best.1) {
+		best_pv = vec![best.1.clone()];
+	}
 
-	return finished_moves[0].clone();
+	return Some(SearchResult {
+		pv: best_pv,
+		best_move: best.1,
+		score: Score::new(best.0),
+		stats,
+	});
 }
 
-pub fn iterative_deepening_no_ext(position: &Position, evaluation_fn: fn(&Position) -> i32, search_time: Duration, transposition_table: Arc<TranspositionTable>) -> (i32, Move) {
-	fn alpha_beta(position: Position, evaluation_fn: fn(&Position) -> i32, mut alpha: i32, beta: i32, depth: u32, extensions_left: u32, end_time: Instant, transposition_table: *mut TranspositionTable) -> (i32, bool) {
+/// Plain iterative deepening without selective search. Doesn't count nodes, so they are reported as 0.
+pub fn iterative_deepening_no_ext(position: &Position, evaluator: Arc<dyn Evaluator>, search_time: Duration, observer: &dyn SearchObserver, transposition_table: Arc<TranspositionTable>) -> SearchResult {
+	fn alpha_beta(position: Position, evaluator: &dyn Evaluator, mut alpha: i32, beta: i32, depth: u32, ply: usize, extensions_left: u32, end_time: Instant, transposition_table: *mut TranspositionTable) -> (i32, bool) {
 		if Instant::now() > end_time {
 			return (0, false);
 		}
 		
 		if depth == 0 {
-			return (evaluation_fn(&position), true);
+			return (evaluator.evaluate(&position), true);
 		};
 
 		unsafe {
@@ -557,10 +1434,7 @@ pub fn iterative_deepening_no_ext(position: &Position, evaluation_fn: fn(&Positi
 
 			if tp.hash_matches(position.hash().value()) {
 				if tp.depth() >= depth {
-					let eval = tp.evaluation();
-					if eval.abs() < 1000000 {
-						return (eval, true);
-					}
+					return (Score::from_transposition(tp.evaluation(), ply).value(), true);
 				}
 			}
 		}
@@ -569,7 +1443,7 @@ pub fn iterative_deepening_no_ext(position: &Position, evaluation_fn: fn(&Positi
 
 		if legal_moves.len() == 0 {
 			if position.is_in_check(position.current_player()) {
-				return (-1000000 * (depth as i32 + 1), true);
+				return (Score::mated_in(ply).value(), true);
 			};
 			
 			return (0, true);
@@ -590,7 +1464,7 @@ pub fn iterative_deepening_no_ext(position: &Position, evaluation_fn: fn(&Positi
 			let mut moved_position = position.clone();
 			moved_position.make_move(m);
 
-			let (mut eval, complete_search) = alpha_beta(moved_position, evaluation_fn, -beta, -alpha, new_depth - 1, extensions_left, end_time, transposition_table);
+			let (mut eval, complete_search) = alpha_beta(moved_position, evaluator, -beta, -alpha, new_depth - 1, ply + 1, extensions_left, end_time, transposition_table);
 			eval = -eval;
 
 			if !complete_search {
@@ -599,7 +1473,7 @@ pub fn iterative_deepening_no_ext(position: &Position, evaluation_fn: fn(&Positi
 
 			let hash = position.hash().value();
 			// Transposition::new(hash, depth - 1, eval)
-			unsafe { *(*transposition_table).get(hash) = Transposition::new(hash, depth - 1, eval) };
+			unsafe { *(*transposition_table).get(hash) = Transposition::new(hash, depth - 1, Score::new(eval).to_transposition(ply), Bound::Exact, None) };
 
 			if eval >= beta {
 				return (eval, complete_search);
@@ -611,7 +1485,8 @@ pub fn iterative_deepening_no_ext(position: &Position, evaluation_fn: fn(&Positi
 		return (alpha, true)
 	}
 
-	let end_time = Instant::now() + search_time;
+	let start_time = Instant::now();
+	let end_time = start_time + search_time;
 
 	let mut depth = 0;
 
@@ -633,11 +1508,12 @@ pub fn iterative_deepening_no_ext(position: &Position, evaluation_fn: fn(&Positi
 
 			let tp = tp_ptr as usize;
 			let m = m.clone();
+			let evaluator = evaluator.clone();
 
 			threads.push(thread::spawn(move || {
 				let tp_ptr = tp as *mut TranspositionTable;
 
-				let (mut eval, complete_search) = alpha_beta(moved_position, evaluation_fn, -beta, -alpha, depth, 4, end_time, tp_ptr);
+				let (mut eval, complete_search) = alpha_beta(moved_position, evaluator.as_ref(), -beta, -alpha, depth, 1, 4, end_time, tp_ptr);
 				eval = -eval;
 
 				return (eval, m.clone(), complete_search);
@@ -663,6 +1539,18 @@ pub fn iterative_deepening_no_ext(position: &Position, evaluation_fn: fn(&Positi
 		evaled_moves.sort_by(|a, b| b.0.cmp(&a.0));
 		finished_moves = evaled_moves.clone();
 
+		observer.notify(SearchEvent::Iteration(SearchInfo {
+			multi_pv: 1,
+			depth: depth + 1,
+			seldepth: depth + 1,
+			score: Score::new(finished_moves[0].0),
+			nodes: 0,
+			nps: 0,
+			hashfull: transposition_table.hashfull(),
+			time: start_time.elapsed(),
+			pv: vec![finished_moves[0].1.clone()],
+		}));
+
 		if depth < 2 {
 			depth += 1;
 		}
@@ -670,16 +1558,10 @@ pub fn iterative_deepening_no_ext(position: &Position, evaluation_fn: fn(&Positi
 			depth += 2;
 		}
 
-		if finished_moves[0].0.abs() > 100000 {
+		if Score::new(finished_moves[0].0).is_mate() {
 			break;
 		}
 	};
 
-	for (eval, m) in finished_moves.iter() {
-		println!("{} | {}", m.debug_string(), eval);
-	}
-
-	println!("Depth: {}", depth);
-
-	return finished_moves[0].clone();
+	return SearchResult::from_move(finished_moves[0].1.clone(), Score::new(finished_moves[0].0));
 }
\ No newline at end of file
@@ -0,0 +1,88 @@
+//! Conditions for ending a search, besides being stopped from outside.
+
+use std::{sync::{Arc, OnceLock}, time::{Duration, Instant}};
+
+use super::time_manager::TimeControl;
+
+/// Limits of a single search. Every limit which is set applies, the search ends at whichever is hit first.
+///
+/// Without any limits the search runs until it is stopped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+	pub time_control: Option<TimeControl>,
+	/// Maximum depth in plies.
+	pub depth: Option<u32>,
+	/// Maximum number of nodes summed over all search threads.
+	pub nodes: Option<u64>,
+	/// Search for a mate in this many moves.
+	pub mate: Option<u32>,
+	/// Ignore the time control and search until stopped.
+	pub infinite: bool,
+	/// Search on the opponent's time. The time control only applies from the ponder hit on.
+	pub ponder: Option<PonderHit>,
+}
+
+impl SearchLimits {
+	/// Limits with only a time control.
+	pub fn from_time_control(time_control: TimeControl) -> Self {
+		Self {
+			time_control: Some(time_control),
+			..Default::default()
+		}
+	}
+
+	/// Limits searching for exactly `move_time`.
+	pub fn move_time(move_time: Duration) -> Self {
+		Self::from_time_control(TimeControl::MoveTime(move_time))
+	}
+
+	/// Time control which applies, [`None`] when the search is infinite.
+	pub fn active_time_control(&self) -> Option<&TimeControl> {
+		if self.infinite {
+			return None;
+		}
+
+		self.time_control.as_ref()
+	}
+
+	/// Maximum depth in plies from the depth and mate limits.
+	pub fn max_depth(&self) -> Option<u32> {
+		// Mate in N moves is found within 2N - 1 plies
+		let mate_depth = self.mate.map(|moves| (2 * moves).saturating_sub(1).max(1));
+
+		match (self.depth, mate_depth) {
+			(Some(depth), Some(mate_depth)) => Some(depth.min(mate_depth)),
+			(depth, mate_depth) => depth.or(mate_depth),
+		}
+	}
+}
+
+/// Signal that the opponent played the move a ponder search expected, turning it into a normal search.
+///
+/// Clones share the signal.
+#[derive(Debug, Clone, Default)]
+pub struct PonderHit(Arc<OnceLock<Instant>>);
+
+impl PonderHit {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Start the clock of the search now. Later calls have no effect.
+	pub fn trigger(&self) {
+		let _ = self.0.set(Instant::now());
+	}
+
+	/// Time of the ponder hit, [`None`] before it.
+	pub fn time(&self) -> Option<Instant> {
+		self.0.get().copied()
+	}
+}
+
+impl PartialEq for PonderHit {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+}
+
+impl Eq for PonderHit {}
@@ -0,0 +1,68 @@
+//! Progress reports of a running search, consumed by the UI, UCI mode and logs.
+
+use std::{fmt, sync::mpsc::Sender, time::Duration};
+
+use crate::board::moove::Move;
+
+use super::score::Score;
+
+/// State of the search after a completed iteration. With several lines there is one per line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchInfo {
+	/// Rank of this line among the best moves, starting at 1. See [`SearchOptions::multi_pv`][super::search_options::SearchOptions::multi_pv].
+	pub multi_pv: u32,
+	/// Depth of the iteration in plies.
+	pub depth: u32,
+	/// Deepest ply reached, including extensions and quiescence search.
+	pub seldepth: u32,
+	pub score: Score,
+	/// Nodes searched by all threads since the search started.
+	pub nodes: u64,
+	pub nps: u64,
+	/// Permille of the transposition table in use.
+	pub hashfull: u32,
+	/// Time since the search started.
+	pub time: Duration,
+	/// Principal variation, starting with the best move.
+	pub pv: Vec<Move>,
+}
+
+impl fmt::Display for SearchInfo {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "line {} depth {}/{} score {} nodes {} nps {} hashfull {} time {:.3}s pv", self.multi_pv, self.depth, self.seldepth, self.score, self.nodes, self.nps, self.hashfull, self.time.as_secs_f64())?;
+
+		for m in &self.pv {
+			write!(f, " {}", m.uci_string())?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Something which happened during a search.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchEvent {
+	/// An iteration of the search finished.
+	Iteration(SearchInfo),
+	/// The move was taken from the opening book without searching.
+	BookMove(Move),
+}
+
+/// Receiver of search progress. Called from the thread running the search.
+pub trait SearchObserver {
+	fn notify(&self, event: SearchEvent);
+}
+
+impl SearchObserver for Sender<SearchEvent> {
+	fn notify(&self, event: SearchEvent) {
+		// Nobody listening anymore is fine
+		let _ = self.send(event);
+	}
+}
+
+/// Observer ignoring every event.
+pub struct NullObserver;
+
+impl SearchObserver for NullObserver {
+	fn notify(&self, _event: SearchEvent) {}
+}
@@ -0,0 +1,93 @@
+//! Settings of [`iterative_deepening`][super::search_funcs::iterative_deepening], mostly switches for its selective search features.
+
+use std::thread;
+
+/// Selective search features which can be turned on and off individually.
+///
+/// Turning every feature off gives the plain alpha-beta search, which is useful for A/B testing new features.
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+	/// Skip a turn and search with reduced depth to prove a fail-high cheaply.
+	pub null_move_pruning: bool,
+	/// Search quiet moves late in the move order with reduced depth, re-searching them on fail-high.
+	pub late_move_reductions: bool,
+	/// Resolve captures at the leaves with a quiescence search instead of evaluating directly.
+	pub quiescence_search: bool,
+	/// Return early when the static evaluation is far above beta at shallow depth.
+	pub reverse_futility_pruning: bool,
+	/// Drop into quiescence search when the static evaluation is far below alpha at shallow depth.
+	pub razoring: bool,
+	/// Skip quiet moves at depth 1-2 when the static evaluation plus a margin can't reach alpha.
+	pub futility_pruning: bool,
+	/// Skip the remaining quiet moves at shallow depth once enough of them have been searched.
+	pub late_move_pruning: bool,
+	/// Search the root with a narrow window around the previous iteration's score, widening it on failure.
+	pub aspiration_windows: bool,
+	/// Search one ply deeper after a checking move.
+	pub check_extensions: bool,
+	/// Search one ply deeper after recapturing on the square of the previous capture.
+	pub recapture_extensions: bool,
+	/// Search one ply deeper after a pawn advances to the 7th rank.
+	pub passed_pawn_extensions: bool,
+	/// Search the transposition table move one ply deeper when every other move is clearly worse.
+	pub singular_extensions: bool,
+	/// Number of best root moves searched with exact scores and reported as separate lines.
+	/// More than one makes the search slower, it is meant for analysis.
+	pub multi_pv: usize,
+	/// Centipawns a draw is scored below zero for the bot, so that it avoids draws against weaker opponents.
+	/// Negative values make it seek draws.
+	pub contempt: i32,
+	/// Largest number of centipawns randomly added to or taken from evaluations, to weaken the bot.
+	/// The noise only depends on the position within a search, so transpositions still agree.
+	pub eval_noise: i32,
+	/// Threads searching the root moves after the first one in parallel. With a single thread the search only
+	/// depends on the position and the transposition table, so it finds the same move every time.
+	pub threads: usize,
+}
+
+impl SearchOptions {
+	/// Options with every selective feature turned off.
+	pub fn disabled() -> Self {
+		Self {
+			null_move_pruning: false,
+			late_move_reductions: false,
+			quiescence_search: false,
+			reverse_futility_pruning: false,
+			razoring: false,
+			futility_pruning: false,
+			late_move_pruning: false,
+			aspiration_windows: false,
+			check_extensions: false,
+			recapture_extensions: false,
+			passed_pawn_extensions: false,
+			singular_extensions: false,
+			multi_pv: 1,
+			contempt: 0,
+			eval_noise: 0,
+			threads: thread::available_parallelism().map_or(1, |n| n.get()),
+		}
+	}
+}
+
+impl Default for SearchOptions {
+	fn default() -> Self {
+		Self {
+			null_move_pruning: true,
+			late_move_reductions: true,
+			quiescence_search: true,
+			reverse_futility_pruning: true,
+			razoring: true,
+			futility_pruning: true,
+			late_move_pruning: true,
+			aspiration_windows: true,
+			check_extensions: true,
+			recapture_extensions: true,
+			passed_pawn_extensions: true,
+			singular_extensions: true,
+			multi_pv: 1,
+			contempt: 0,
+			eval_noise: 0,
+			threads: thread::available_parallelism().map_or(1, |n| n.get()),
+		}
+	}
+}
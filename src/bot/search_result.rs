@@ -0,0 +1,45 @@
+//! Outcome of a finished search.
+
+use std::time::Duration;
+
+use crate::board::moove::Move;
+
+use super::score::Score;
+
+/// Best move of a search with the line and statistics behind it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+	pub best_move: Move,
+	pub score: Score,
+	/// Principal variation, starting with the best move.
+	pub pv: Vec<Move>,
+	pub stats: SearchStats,
+}
+
+/// Work done by a search.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchStats {
+	/// Depth of the last completed iteration in plies.
+	pub depth: u32,
+	/// Deepest ply reached, including extensions and quiescence search.
+	pub seldepth: u32,
+	pub nodes: u64,
+	pub time: Duration,
+}
+
+impl SearchResult {
+	/// Result without a principal variation beyond the move, for bots which don't search.
+	pub fn from_move(best_move: Move, score: Score) -> Self {
+		Self {
+			pv: vec![best_move.clone()],
+			best_move,
+			score,
+			stats: SearchStats::default(),
+		}
+	}
+
+	/// Reply expected to the best move, which the bot can ponder on.
+	pub fn ponder_move(&self) -> Option<&Move> {
+		self.pv.get(1)
+	}
+}
@@ -0,0 +1,142 @@
+//! Deciding how long a search may take from the clock situation.
+
+use std::time::{Duration, Instant};
+
+/// Time kept in reserve for the delay between the search finishing and the move being played.
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+/// Moves the remaining time is split over when the time control doesn't say.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+/// The hard limit is at most this many times the soft limit.
+const HARD_LIMIT_FACTOR: u32 = 4;
+
+/// How much each recent best move change extends the soft limit.
+const BEST_MOVE_INSTABILITY_FACTOR: f64 = 0.5;
+/// Score drops smaller than this between iterations don't extend the search.
+const SCORE_DROP_MARGIN: i32 = 30;
+/// Score drop at which the soft limit is doubled.
+const SCORE_DROP_MAX: i32 = 150;
+/// Fraction of the soft limit used when the best move is obvious.
+const OBVIOUS_MOVE_FACTOR: f64 = 0.2;
+/// A new iteration isn't started after this fraction of the soft limit, since it would hardly ever finish.
+const NEXT_ITERATION_FRACTION: f64 = 0.6;
+
+/// Time available for a single search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeControl {
+	/// Search exactly this long.
+	MoveTime(Duration),
+	/// Time left on the clock of the player to move.
+	Clock {
+		remaining: Duration,
+		increment: Duration,
+		/// Moves until the next time control, `None` for sudden death.
+		moves_to_go: Option<u32>,
+	},
+}
+
+/// Soft and hard time limits of a search.
+///
+/// The hard limit is a deadline the search is aborted at. The soft limit is checked between iterations
+/// and is stretched when the search is unstable and shrunk when the best move is obvious.
+pub struct TimeManager {
+	start: Instant,
+	soft_limit: Duration,
+	hard_limit: Duration,
+	/// Fixed move times are used as is, without extending or stopping early.
+	fixed: bool,
+	best_move_changes: f64,
+	previous_score: Option<i32>,
+	obvious_move: bool,
+}
+
+impl TimeManager {
+	pub fn new(time_control: &TimeControl) -> Self {
+		let (soft_limit, hard_limit, fixed) = match time_control {
+			TimeControl::MoveTime(move_time) => (*move_time, *move_time, true),
+			TimeControl::Clock { remaining, increment, moves_to_go } => {
+				let available = remaining.saturating_sub(MOVE_OVERHEAD);
+				let moves = moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).clamp(1, DEFAULT_MOVES_TO_GO);
+
+				// The last move before the time control may use everything, otherwise keep half for later moves.
+				let max_time = if moves == 1 { available } else { available / 2 };
+
+				let hard_limit = ((available / moves + *increment * 3 / 4) * HARD_LIMIT_FACTOR).min(max_time);
+				let soft_limit = (available / moves + *increment * 3 / 4).min(hard_limit);
+
+				(soft_limit, hard_limit, false)
+			},
+		};
+
+		Self {
+			start: Instant::now(),
+			soft_limit,
+			hard_limit,
+			fixed,
+			best_move_changes: 0.0,
+			previous_score: None,
+			obvious_move: false,
+		}
+	}
+
+	pub fn soft_limit(&self) -> Duration {
+		self.soft_limit
+	}
+
+	pub fn hard_limit(&self) -> Duration {
+		self.hard_limit
+	}
+
+	/// Count the time from `start` instead of the creation, used when the search started pondering.
+	pub fn restart_at(&mut self, start: Instant) {
+		self.start = start;
+	}
+
+	pub fn elapsed(&self) -> Duration {
+		self.start.elapsed()
+	}
+
+	/// Return `true` if the best move may be checked for being obvious. That is only once enough time has passed that
+	/// an obvious move would end the search, as the check costs a search of its own.
+	pub fn can_stop_early(&self) -> bool {
+		!self.fixed && !self.obvious_move && self.elapsed() >= self.soft_limit.mul_f64(OBVIOUS_MOVE_FACTOR * NEXT_ITERATION_FRACTION)
+	}
+
+	/// Shrink the soft limit because the best move is clearly better than the rest.
+	pub fn set_obvious_move(&mut self) {
+		self.obvious_move = true;
+	}
+
+	/// Soft limit scaled by the search stability seen so far.
+	fn optimum(&self) -> Duration {
+		if self.obvious_move {
+			return self.soft_limit.mul_f64(OBVIOUS_MOVE_FACTOR);
+		}
+
+		let scale = 1.0 + self.best_move_changes * BEST_MOVE_INSTABILITY_FACTOR;
+
+		self.soft_limit.mul_f64(scale).min(self.hard_limit)
+	}
+
+	/// Update the stability with a finished iteration and return `true` if the next iteration shouldn't be started.
+	pub fn iteration_finished(&mut self, best_move_changed: bool, score: i32) -> bool {
+		// Older changes count less
+		self.best_move_changes = self.best_move_changes * 0.5 + if best_move_changed { 1.0 } else { 0.0 };
+
+		let score_drop = self.previous_score.map_or(0, |previous_score| previous_score.saturating_sub(score));
+		self.previous_score = Some(score);
+
+		if self.fixed {
+			return self.elapsed() >= self.hard_limit;
+		}
+
+		let mut optimum = self.optimum();
+
+		// Falling score means trouble, so spend more time finding a way out
+		if !self.obvious_move && score_drop > SCORE_DROP_MARGIN {
+			let drop_scale = 1.0 + score_drop.min(SCORE_DROP_MAX) as f64 / SCORE_DROP_MAX as f64;
+			optimum = optimum.mul_f64(drop_scale).min(self.hard_limit);
+		}
+
+		self.elapsed() >= optimum.mul_f64(NEXT_ITERATION_FRACTION)
+	}
+}
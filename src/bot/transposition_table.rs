@@ -1,6 +1,11 @@
 use std::{mem, sync::atomic::{AtomicU64, Ordering}};
 
+use crate::{board::moove::Move, player::Player};
+
 const TABLE_SIZE: usize = 200000000;
+const MEGABYTE: usize = 1 << 20;
+/// Size of the default table in megabytes.
+pub const DEFAULT_SIZE_MB: usize = TABLE_SIZE * mem::size_of::<Transposition>() / MEGABYTE;
 
 pub struct TranspositionTable {
 	map: Vec<Transposition>,
@@ -10,10 +15,15 @@ pub struct TranspositionTable {
 
 impl TranspositionTable {
 	pub fn new() -> Self {
-		let mut map = Vec::with_capacity(TABLE_SIZE);
+		Self::with_size(TABLE_SIZE)
+	}
+
+	/// Create a table with `size` entries instead of the default size.
+	pub fn with_size(size: usize) -> Self {
+		let mut map = Vec::with_capacity(size);
 
-		for _ in 0..TABLE_SIZE {
-			map.push(Transposition::new(0, 0, 0));
+		for _ in 0..size {
+			map.push(Transposition::empty());
 		}
 
 		Self {
@@ -23,8 +33,17 @@ impl TranspositionTable {
 		}
 	}
 
+	/// Create a table taking about `megabytes` of memory.
+	pub fn with_size_mb(megabytes: usize) -> Self {
+		Self::with_size((megabytes * MEGABYTE / mem::size_of::<Transposition>()).max(1))
+	}
+
+	pub fn size_mb(&self) -> usize {
+		self.map.len() * mem::size_of::<Transposition>() / MEGABYTE
+	}
+
 	pub fn get(&mut self, hash: u64) -> &mut Transposition {
-		let index = hash as usize % TABLE_SIZE;
+		let index = hash as usize % self.map.len();
 		let res = &mut self.map[index];
 
 		return res;
@@ -36,10 +55,25 @@ impl TranspositionTable {
 	// 	self.map[index] = transposition;
 	// }
 
+	/// Empty every entry. Must not be called while a search is using the table.
+	pub fn clear(&self) {
+		for tp in &self.map {
+			tp.clear();
+		}
+	}
+
 	pub fn len(&self) -> usize {
 		self.map.len()
 	}
 
+	/// Permille of used entries, sampled from the first thousand.
+	pub fn hashfull(&self) -> u32 {
+		let sample = &self.map[..self.map.len().min(1000)];
+		let used = sample.iter().filter(|tp| !tp.is_empty()).count();
+
+		(used * 1000 / sample.len().max(1)) as u32
+	}
+
 	pub fn lookups(&self) -> u64 {
 		self.lookups
 	}
@@ -58,6 +92,37 @@ impl TranspositionTable {
 	}
 }
 
+/// Tells how the stored evaluation relates to the real value of the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+	/// Evaluation is the exact value.
+	Exact,
+	/// Search failed high, real value is at least the evaluation.
+	Lower,
+	/// Search failed low, real value is at most the evaluation.
+	Upper,
+}
+
+impl Bound {
+	fn to_bits(self) -> u64 {
+		match self {
+			Self::Exact => 0,
+			Self::Lower => 1,
+			Self::Upper => 2,
+		}
+	}
+
+	fn from_bits(bits: u64) -> Self {
+		match bits {
+			1 => Self::Lower,
+			2 => Self::Upper,
+			_ => Self::Exact,
+		}
+	}
+}
+
+/// Single table entry. The value is packed as:
+/// bits 0-31 evaluation, bits 32-39 depth, bits 40-41 [`Bound`] and bits 48-63 best move (see [`Move::to_u16`]).
 pub struct Transposition {
 	hash_check: AtomicU64,
 	value: AtomicU64,
@@ -65,11 +130,13 @@ pub struct Transposition {
 }
 
 impl Transposition {
-	pub fn new(hash: u64, depth: u32, evaluation: i32) -> Self {
-		let depth_u64 = (depth as u64) << 32;
+	pub fn new(hash: u64, depth: u32, evaluation: i32, bound: Bound, best_move: Option<&Move>) -> Self {
+		let depth_u64 = (depth.min(u8::MAX as u32) as u64) << 32;
 		let eval_u64 = unsafe { mem::transmute::<_, u32>(evaluation) } as u64;
+		let bound_u64 = bound.to_bits() << 40;
+		let move_u64 = (best_move.map_or(0, |m| m.to_u16()) as u64) << 48;
 
-		let value = depth_u64 | eval_u64;
+		let value = move_u64 | bound_u64 | depth_u64 | eval_u64;
 		let hash_check = hash ^ value;
 
 		Self {
@@ -78,18 +145,46 @@ impl Transposition {
 		}
 	}
 
+	/// Entry which doesn't match any position.
+	pub fn empty() -> Self {
+		Self {
+			hash_check: AtomicU64::new(0),
+			value: AtomicU64::new(0)
+		}
+	}
+
+	/// Turn the entry into an [`empty`][Self::empty] one.
+	pub fn clear(&self) {
+		self.hash_check.store(0, Ordering::Relaxed);
+		self.value.store(0, Ordering::Relaxed);
+	}
+
+	/// Return `true` if nothing has been stored in the entry.
+	pub fn is_empty(&self) -> bool {
+		self.value.load(Ordering::Relaxed) == 0 && self.hash_check.load(Ordering::Relaxed) == 0
+	}
+
 	pub fn depth(&self) -> u32 {
-		let depth = self.value.load(Ordering::Relaxed) >> 32;
+		let depth = self.value.load(Ordering::Relaxed) >> 32 & 0xFF;
 
 		return depth as u32;
 	}
 
 	pub fn evaluation(&self) -> i32 {
-		let eval = (self.value.load(Ordering::Relaxed) | 0xFFFFFFFF) as u32;
+		let eval = (self.value.load(Ordering::Relaxed) & 0xFFFFFFFF) as u32;
 
 		return unsafe { mem::transmute(eval) }
 	}
 
+	pub fn bound(&self) -> Bound {
+		Bound::from_bits(self.value.load(Ordering::Relaxed) >> 40 & 0b11)
+	}
+
+	/// Best move found for the position. `player` is the [`Player`] to move in the position.
+	pub fn best_move(&self, player: Player) -> Option<Move> {
+		Move::from_u16((self.value.load(Ordering::Relaxed) >> 48) as u16, player)
+	}
+
 	pub fn hash_matches(&self, hash: u64) -> bool {
 		return (self.value.load(Ordering::Relaxed) ^ self.hash_check.load(Ordering::Relaxed)) == hash;
 	}
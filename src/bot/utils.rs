@@ -5,25 +5,24 @@ use crate::{
     player::Player,
 };
 
+pub const BISHOP_PAIR_BONUS: (i32, i32) = (22, 88);
+pub const OPEN_COLUMN_BONUS: (i32, i32) = (8, 20);
+
 /// Calculates game's phase based on amount of pieces on the board.
 /// Returns a tuple that works as percentages: (100, 0) = 100% in early/midgame, (0 , 100) = 100% in lategame.
 pub fn calculate_game_phase(position: &Position) -> (i32, i32) {
-    let mut position_materials = 0;
     let full_board = 24;
 
-    position_materials += position.board().queens.value().count_ones() * 4;
-    position_materials += position.board().rooks.value().count_ones() * 2;
-    position_materials += position.board().bishops.value().count_ones();
-    position_materials += position.board().knights.value().count_ones();
+    // Queens count 4, rooks 2, bishops and knights 1, kept up to date by the position
+    let position_materials = position.piece_square_score().phase();
 
-    let midgame_percentage = (position_materials * 100 / full_board) as i32;
+    let midgame_percentage = position_materials * 100 / full_board;
     let endgame_percentage = 100 - midgame_percentage;
 
     (midgame_percentage, endgame_percentage)
 }
 
 pub fn bishop_pair_bonus(position: &Position, game_phase: (i32, i32)) -> i32 {
-    const BISHOP_PAIR_BONUS: (i32, i32) = ( 22, 88);
     let mut score = 0;
 
     let player_board = position
@@ -71,8 +70,6 @@ pub fn rook_pair_penalty(position: &Position) -> i32 {
 }
 
 pub fn rook_open_column_bonus(position: &Position, game_phase: (i32, i32)) -> i32 {
-    const OPEN_COLUMN_BONUS: (i32, i32) = (8, 20);
-
     let pawn_board = position.board().pawns;
     let mut score = 0;
 
@@ -112,93 +109,3 @@ pub fn rook_open_column_bonus(position: &Position, game_phase: (i32, i32)) -> i3
         -score / 100
     }
 }
-
-pub fn passed_pawns_bonus(position: &Position) -> i32 {
-    const PASSED_PAWN_BONUS: i32 = 20;
-    let mut score = 0;
-
-    let pawn_board = position.board().pawns;
-
-    let white_player_board = *position.board().get_player_bitboard(Player::White);
-
-    let black_player_board = *position.board().get_player_bitboard(Player::Black);
-
-    let white_pawn_board = pawn_board & white_player_board;
-    let mut white_pawn_board_copy = white_pawn_board;
-    let black_pawn_board = pawn_board & black_player_board;
-    let mut black_pawn_board_copy = black_pawn_board;
-
-    while white_pawn_board_copy != 0 {
-        let bit_offset = white_pawn_board_copy.pop_lsb();
-        let pawn_position = TilePosition::from_bit_offset(bit_offset);
-
-        let mut passed_check_mask = Bitboard::generate_column_mask(pawn_position.column())
-            << (pawn_position.rank() + 1) as u64 * 8;
-
-        if pawn_position.column() != 0 {
-            passed_check_mask |= Bitboard::generate_column_mask(pawn_position.column() - 1)
-                << (pawn_position.rank() + 1) as u64 * 8
-        }
-
-        if pawn_position.column() != 7 {
-            passed_check_mask |= Bitboard::generate_column_mask(pawn_position.column() + 1)
-                << (pawn_position.rank() + 1) as u64 * 8
-        }
-
-        if passed_check_mask & black_pawn_board == 0 {
-            score += PASSED_PAWN_BONUS;
-        }
-    }
-
-    while black_pawn_board_copy != 0 {
-        let bit_offset = black_pawn_board_copy.pop_lsb();
-        let pawn_position = TilePosition::from_bit_offset(bit_offset);
-
-        let mut passed_check_mask = Bitboard::generate_column_mask(pawn_position.column())
-            >> (8 - pawn_position.rank()) as u64 * 8;
-
-        if pawn_position.column() != 0 {
-            passed_check_mask |= Bitboard::generate_column_mask(pawn_position.column() - 1)
-                >> (8 - pawn_position.rank()) as u64 * 8
-        }
-
-        if pawn_position.column() != 7 {
-            passed_check_mask |= Bitboard::generate_column_mask(pawn_position.column() + 1)
-                >> (8 - pawn_position.rank()) as u64 * 8
-        }
-
-        if passed_check_mask & white_pawn_board == 0 {
-            score -= PASSED_PAWN_BONUS;
-        }
-    }
-
-    if position.current_player() == Player::White {
-        score
-    } else {
-        -score
-    }
-}
-
-pub fn no_pawn_penalty(position: &Position) -> i32 {
-    const NO_PAWN_PENALTY: i32 = -50;
-    let mut score = 0;
-
-    let white_pawn_board =
-        *position.board().get_player_bitboard(Player::White) & position.board().pawns;
-
-    let black_pawn_board =
-        *position.board().get_player_bitboard(Player::Black) & position.board().pawns;
-
-    if white_pawn_board == 0 {
-        score += NO_PAWN_PENALTY;
-    }
-    if black_pawn_board == 0 {
-        score -= NO_PAWN_PENALTY
-    }
-
-    if position.current_player() == Player::White {
-        score
-    } else {
-        -score
-    }
-}
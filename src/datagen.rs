@@ -0,0 +1,229 @@
+//! Self-play training data generation, used instead of the UI when started with `--datagen`.
+//!
+//! Bots play games against themselves from openings of random moves. Quiet positions of the games are written as
+//! text, one per line:
+//!
+//! ```text
+//! <fen> | <score> | <result>
+//! ```
+//!
+//! `score` is the search score in centipawns and `result` is `1.0`, `0.5` or `0.0` for a win, draw or loss, both from
+//! White's point of view. Positions in check, with a capture available or with a mate score are left out.
+//!
+//! The bots of [`datagen_bot`] search single-threaded without the opening book, so the data only depends on the seed
+//! and the search limits.
+
+use std::{collections::BTreeMap, io::{self, Write}, sync::{atomic::{AtomicUsize, Ordering}, mpsc}, thread};
+
+use rand::{seq::IndexedRandom, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::{board::{game_state::GameState, moove::Move, position::Position}, bot::{eval_params::EvalParams, evaluator::ClassicalEvaluator, iterative_deepening_search::{IterativeDeepeningSearch, OWN_BOOK_OPTION}, move_ordering::is_capture, nnue::NnueEvaluator, search_limits::SearchLimits, search_observer::NullObserver, search_options::SearchOptions, Bot}, player::Player};
+
+/// Games ending later than this are adjudicated as draws.
+const MAX_GAME_PLIES: usize = 400;
+/// Transposition table size of each data generation thread.
+const DATAGEN_HASH_MB: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct DatagenOptions {
+    pub games: usize,
+    pub threads: usize,
+    /// Seed of the first game's opening, the following games use the next seeds.
+    pub seed: u64,
+    /// Random moves played before the bots take over.
+    pub random_plies: usize,
+    pub limits: SearchLimits,
+}
+
+impl Default for DatagenOptions {
+    fn default() -> Self {
+        Self {
+            games: 100,
+            threads: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            seed: 0,
+            random_plies: 8,
+            limits: SearchLimits {
+                depth: Some(6),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Quiet position of a game with its search score from White's point of view.
+struct Sample {
+    fen: String,
+    score: i32,
+}
+
+/// Bot playing the data generation games, evaluating with the network if one is loaded and with `eval_params`
+/// otherwise. Its search is forced to a single thread, so that it plays the same moves on every run.
+pub fn datagen_bot(eval_params: &EvalParams, search_options: &SearchOptions) -> Box<dyn Bot> {
+    let evaluator = NnueEvaluator::new(ClassicalEvaluator::new(eval_params.clone()));
+    let search_options = SearchOptions {
+        threads: 1,
+        ..search_options.clone()
+    };
+    let mut bot = IterativeDeepeningSearch::with_hash_size(evaluator, true, search_options, DATAGEN_HASH_MB);
+
+    // Book moves are random and come without a score
+    bot.set_option(OWN_BOOK_OPTION, "false").unwrap();
+
+    Box::new(bot)
+}
+
+/// Play `options.games` games with bots made by `new_bot`, one per thread, and write their positions to `output`
+/// ordered by game. The seed fixes the openings, so with bots from [`datagen_bot`] and a depth or node limit the
+/// output only depends on it. Returns the number of positions written.
+pub fn generate_data(options: &DatagenOptions, new_bot: impl Fn() -> Box<dyn Bot> + Sync, output: &mut impl Write) -> io::Result<usize> {
+    let next_game = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..options.threads.max(1) {
+            let sender = sender.clone();
+            let next_game = &next_game;
+            let new_bot = &new_bot;
+
+            scope.spawn(move || {
+                let mut bot = new_bot();
+
+                loop {
+                    let game = next_game.fetch_add(1, Ordering::Relaxed);
+
+                    if game >= options.games {
+                        break;
+                    }
+
+                    bot.new_game();
+
+                    let lines = play_game(bot.as_ref(), options, options.seed.wrapping_add(game as u64));
+
+                    if sender.send((game, lines)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        // Ends once every thread is done and has dropped its sender
+        drop(sender);
+
+        let mut finished = BTreeMap::new();
+        let mut next_to_write = 0;
+        let mut positions = 0;
+
+        for (game, lines) in receiver {
+            finished.insert(game, lines);
+
+            while let Some(lines) = finished.remove(&next_to_write) {
+                for line in &lines {
+                    writeln!(output, "{}", line)?;
+                }
+
+                positions += lines.len();
+                next_to_write += 1;
+            }
+
+            println!("Finished {}/{} games, {} positions", next_to_write, options.games, positions);
+        }
+
+        output.flush()?;
+
+        Ok(positions)
+    })
+}
+
+/// Play a game from an opening chosen with `seed` and return the lines of its quiet positions.
+fn play_game(bot: &dyn Bot, options: &DatagenOptions, seed: u64) -> Vec<String> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let mut history = vec![Position::default()];
+
+    for _ in 0..options.random_plies {
+        let position = history.last().unwrap();
+
+        let Some(moove) = position.get_all_legal_moves().choose(&mut rng).cloned() else {
+            // The opening ended the game, which gives nothing to learn from
+            return Vec::new();
+        };
+
+        push_move(&mut history, moove);
+    }
+
+    let mut samples = Vec::new();
+
+    let result = loop {
+        let position = history.last().unwrap();
+
+        if let Some(result) = game_result(&history) {
+            break result;
+        }
+
+        if history.len() > MAX_GAME_PLIES + 1 {
+            break 0.5;
+        }
+
+        let search_result = bot.search(&history, &options.limits, &NullObserver);
+        let score = search_result.score;
+
+        if is_quiet(position) && !score.is_mate() {
+            samples.push(Sample {
+                fen: position.to_fen_string(),
+                score: if position.current_player() == Player::White { score.value() } else { -score.value() },
+            });
+        }
+
+        push_move(&mut history, search_result.best_move);
+    };
+
+    return samples.iter().map(|sample| format!("{} | {} | {:.1}", sample.fen, sample.score, result)).collect();
+}
+
+fn push_move(history: &mut Vec<Position>, moove: Move) {
+    let mut position = history.last().unwrap().clone();
+
+    position.make_move(moove);
+    history.push(position);
+}
+
+/// Result of the game from White's point of view if it has ended.
+fn game_result(history: &[Position]) -> Option<f32> {
+    let position = history.last().unwrap();
+
+    match position.get_game_state() {
+        GameState::Checkmate(Player::White) => return Some(1.0),
+        GameState::Checkmate(Player::Black) => return Some(0.0),
+        GameState::Stalemate => return Some(0.5),
+        GameState::Ongoing => {},
+    }
+
+    let repetitions = history.iter().filter(|p| p.hash().value() == position.hash().value()).count();
+
+    if position.halfmove_clock() >= 100 || repetitions >= 3 || is_insufficient_material(position) {
+        return Some(0.5);
+    }
+
+    None
+}
+
+/// Return `true` if neither player can checkmate: only kings, or kings and a single knight or bishop.
+fn is_insufficient_material(position: &Position) -> bool {
+    let board = position.board();
+
+    if !(board.pawns | board.rooks | board.queens).is_empty() {
+        return false;
+    }
+
+    return (board.knights | board.bishops).0.count_ones() <= 1;
+}
+
+/// Return `true` if the player to move isn't in check and has no captures, so the evaluation alone should find the
+/// score.
+fn is_quiet(position: &Position) -> bool {
+    if position.is_in_check(position.current_player()) {
+        return false;
+    }
+
+    return !position.get_all_legal_moves().iter().any(|m| is_capture(position, m));
+}
@@ -1,7 +1,12 @@
-use std::env;
+use std::{env, fs::File, io::BufWriter, time::Duration};
 
+use analysis::{analyse, print_evaluation};
 use board::zobrist_hash::generate_zobrist_numbers;
+use bot::{create_bot, eval_params::EvalParams, limited_strength::{Strength, MAX_SKILL_LEVEL}, nnue, search_limits::SearchLimits, search_options::SearchOptions, time_manager::TimeControl, BOT_NAMES};
+use datagen::{datagen_bot, generate_data, DatagenOptions};
+use tuner::{load_positions, tune, TunerOptions};
 use performance_test::performance_test;
+use uci::start_uci;
 use ui::start_ui;
 
 pub mod board;
@@ -18,6 +23,13 @@ pub mod tests;
 mod performance_test;
 pub mod r#match;
 pub mod opening_book;
+mod uci;
+mod analysis;
+mod datagen;
+mod tuner;
+
+/// File the tuned parameters are written to without `--tune-output`.
+const DEFAULT_TUNE_OUTPUT: &str = "tuned_params.txt";
 
 fn main() {
     generate_zobrist_numbers();
@@ -31,8 +43,175 @@ fn main() {
         return;
     }
 
-    let white_bot = args.contains(&"--white_bot".to_owned());
-    let black_bot = args.contains(&"--black_bot".to_owned());
+    let search_options = parse_search_options(&args);
+
+    let eval_params = match flag_value(&args, "--eval-params").map(EvalParams::load) {
+        Some(Ok(params)) => params,
+        Some(Err(error)) => {
+            println!("{}", error);
+            return;
+        },
+        None => EvalParams::default(),
+    };
+
+    if let Some(path) = flag_value(&args, "--nnue") {
+        if let Err(error) = nnue::load_network(path) {
+            println!("{}", error);
+            return;
+        }
+    }
+
+    if let Some(path) = flag_value(&args, "--datagen") {
+        let new_bot = || datagen_bot(&eval_params, &search_options);
+        let result = File::create(path).and_then(|file| generate_data(&parse_datagen_options(&args), new_bot, &mut BufWriter::new(file)));
+
+        match result {
+            Ok(positions) => println!("Wrote {} positions to {}", positions, path),
+            Err(error) => println!("{}", error),
+        }
+        return;
+    }
+
+    if let Some(path) = flag_value(&args, "--tune") {
+        let positions = match load_positions(path) {
+            Ok(positions) => positions,
+            Err(error) => {
+                println!("{}", error);
+                return;
+            },
+        };
+
+        let output = flag_value(&args, "--tune-output").unwrap_or(DEFAULT_TUNE_OUTPUT);
+
+        // Saved after every pass, so that stopping early keeps the progress
+        let tuned = tune(&eval_params, &positions, &parse_tuner_options(&args), |params| {
+            if let Err(error) = params.save(output) {
+                println!("Couldn't save parameters: {}", error);
+            }
+        });
+
+        if let Err(error) = tuned.save(output) {
+            println!("Couldn't save parameters: {}", error);
+        } else if tuned == eval_params {
+            println!("No weights changed, the starting parameters are in {}", output);
+        } else {
+            println!("Tuned parameters are in {}", output);
+        }
+        return;
+    }
+
+    if let Some(fen) = flag_value(&args, "--eval") {
+        if let Err(message) = print_evaluation(fen, &eval_params) {
+            println!("{}", message);
+        }
+        return;
+    }
+
+    if let Some(fen) = flag_value(&args, "--analyse") {
+        if let Err(message) = analyse(fen, search_options, eval_params, parse_analysis_limits(&args)) {
+            println!("{}", message);
+        }
+        return;
+    }
+
+    let bot_name = flag_value(&args, "--bot").unwrap_or(BOT_NAMES[0]);
+
+    if !BOT_NAMES.contains(&bot_name) {
+        println!("Unknown bot {}, expected one of: {}", bot_name, BOT_NAMES.join(", "));
+        return;
+    }
+
+    let new_bot = || create_bot(bot_name, search_options.clone(), parse_strength(&args), eval_params.clone()).unwrap();
+
+    if args.contains(&"--uci".to_owned()) {
+        start_uci(new_bot(), eval_params.clone());
+        return;
+    }
+
+    let white_bot = args.contains(&"--white_bot".to_owned()).then(new_bot);
+    let black_bot = args.contains(&"--black_bot".to_owned()).then(new_bot);
 
-    start_ui(white_bot, black_bot);
+    let ponder = args.contains(&"--ponder".to_owned());
+
+    start_ui(white_bot, black_bot, parse_time_control(&args), ponder, eval_params);
+}
+
+/// Value following `flag` in the arguments.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let index = args.iter().position(|a| a == flag)?;
+
+    args.get(index + 1).map(|v| v.as_str())
+}
+
+/// Time control from `--time <seconds>`, `--increment <seconds>` and `--moves-to-go <moves>`.
+/// Without `--time` bots search for `--move-time <seconds>`, 5 seconds by default.
+fn parse_time_control(args: &[String]) -> TimeControl {
+    let seconds = |flag: &str| flag_value(args, flag).and_then(|v| v.parse::<f64>().ok()).map(Duration::from_secs_f64);
+
+    if let Some(remaining) = seconds("--time") {
+        return TimeControl::Clock {
+            remaining,
+            increment: seconds("--increment").unwrap_or(Duration::ZERO),
+            moves_to_go: flag_value(args, "--moves-to-go").and_then(|v| v.parse().ok()),
+        };
+    }
+
+    TimeControl::MoveTime(seconds("--move-time").unwrap_or(Duration::from_secs(5)))
+}
+
+/// Bot settings from `--multi-pv <lines>` and `--contempt <centipawns>`.
+fn parse_search_options(args: &[String]) -> SearchOptions {
+    let defaults = SearchOptions::default();
+
+    SearchOptions {
+        multi_pv: flag_value(args, "--multi-pv").and_then(|v| v.parse().ok()).unwrap_or(defaults.multi_pv),
+        contempt: flag_value(args, "--contempt").and_then(|v| v.parse().ok()).unwrap_or(defaults.contempt),
+        ..defaults
+    }
+}
+
+/// Bot strength from `--elo <elo>` or `--skill <0-20>`, full strength by default.
+fn parse_strength(args: &[String]) -> Strength {
+    if let Some(elo) = flag_value(args, "--elo").and_then(|v| v.parse().ok()) {
+        return Strength::Elo(elo);
+    }
+
+    Strength::Skill(flag_value(args, "--skill").and_then(|v| v.parse().ok()).unwrap_or(MAX_SKILL_LEVEL))
+}
+
+/// Analysis searches to `--depth <plies>` when it is given, otherwise for the time control.
+fn parse_analysis_limits(args: &[String]) -> SearchLimits {
+    match flag_value(args, "--depth").and_then(|v| v.parse().ok()) {
+        Some(depth) => SearchLimits {
+            depth: Some(depth),
+            ..Default::default()
+        },
+        None => SearchLimits::from_time_control(parse_time_control(args)),
+    }
+}
+
+/// Data generation settings from `--games`, `--threads`, `--seed`, `--random-plies` and `--depth`.
+fn parse_datagen_options(args: &[String]) -> DatagenOptions {
+    let defaults = DatagenOptions::default();
+
+    DatagenOptions {
+        games: flag_value(args, "--games").and_then(|v| v.parse().ok()).unwrap_or(defaults.games),
+        threads: flag_value(args, "--threads").and_then(|v| v.parse().ok()).unwrap_or(defaults.threads),
+        seed: flag_value(args, "--seed").and_then(|v| v.parse().ok()).unwrap_or(defaults.seed),
+        random_plies: flag_value(args, "--random-plies").and_then(|v| v.parse().ok()).unwrap_or(defaults.random_plies),
+        limits: SearchLimits {
+            depth: flag_value(args, "--depth").and_then(|v| v.parse().ok()).or(defaults.limits.depth),
+            ..Default::default()
+        },
+    }
+}
+
+/// Tuning settings from `--passes` and `--threads`.
+fn parse_tuner_options(args: &[String]) -> TunerOptions {
+    let defaults = TunerOptions::default();
+
+    TunerOptions {
+        passes: flag_value(args, "--passes").and_then(|v| v.parse().ok()).unwrap_or(defaults.passes),
+        threads: flag_value(args, "--threads").and_then(|v| v.parse().ok()).unwrap_or(defaults.threads),
+    }
 }
@@ -1,8 +1,22 @@
 //! Match initiation and handling. Also handles bot moves.
 
-use std::{thread::{self, JoinHandle}, time::Duration};
+use std::{sync::{mpsc::{self, Receiver, TryRecvError}, Arc}, thread::{self, JoinHandle}, time::{Duration, Instant}};
 
-use crate::{board::{game_state::GameState, moove::Move, position::Position}, bot::Bot, player::Player};
+use crate::{board::{game_state::GameState, moove::Move, position::Position}, bot::{bot_option::{BotOption, OptionError}, search_limits::{PonderHit, SearchLimits}, search_observer::SearchEvent, search_result::SearchResult, time_manager::TimeControl, Bot}, player::Player};
+
+/// Bot search running on another thread.
+struct Search {
+    thread: JoinHandle<()>,
+    receiver: Receiver<SearchResult>,
+    /// Copy of the bot running the search, kept to stop it.
+    bot: Arc<dyn Bot>,
+}
+
+/// Search on the opponent's time, assuming they play `expected_move`.
+struct Ponder {
+    expected_move: Move,
+    ponder_hit: PonderHit,
+}
 
 pub struct Match {
     position: Vec<Position>,
@@ -10,18 +24,47 @@ pub struct Match {
     white_bot: Option<Box<dyn Bot>>,
     black_bot: Option<Box<dyn Bot>>,
 
-    search_thread: Option<JoinHandle<(i32, Move)>>,
-    search_time: Duration,
+    search: Option<Search>,
+    /// Result of a finished search which hasn't been taken yet.
+    searched_move: Option<SearchResult>,
+    /// Reply the bot expected to its last move.
+    ponder_move: Option<Move>,
+    /// Whether bots search on the opponent's time.
+    pondering: bool,
+    /// Set while the running search is pondering.
+    ponder: Option<Ponder>,
+    /// Progress of the latest search.
+    search_events: Option<Receiver<SearchEvent>>,
+    time_control: TimeControl,
+
+    /// Remaining time of White and Black, only used with [`TimeControl::Clock`].
+    clocks: [Duration; 2],
+    /// Moves made by White and Black, used to count down to the next time control.
+    moves_made: [u32; 2],
+    turn_start: Instant,
 }
 
 impl Match {
-    pub fn new(position: &Position, white_bot: Option<Box<dyn Bot>>, black_bot: Option<Box<dyn Bot>>, search_time: Duration) -> Self {
+    pub fn new(position: &Position, white_bot: Option<Box<dyn Bot>>, black_bot: Option<Box<dyn Bot>>, time_control: TimeControl) -> Self {
+        let starting_time = match &time_control {
+            TimeControl::Clock { remaining, .. } => *remaining,
+            TimeControl::MoveTime(_) => Duration::ZERO,
+        };
+
         Self {
             position: vec![position.clone()],
             white_bot,
             black_bot,
-            search_thread: None,
-            search_time
+            search: None,
+            searched_move: None,
+            ponder_move: None,
+            pondering: false,
+            ponder: None,
+            search_events: None,
+            time_control,
+            clocks: [starting_time; 2],
+            moves_made: [0; 2],
+            turn_start: Instant::now(),
         }
     }
 
@@ -30,17 +73,133 @@ impl Match {
     }
 
     pub fn set_position(&mut self, position: &Position) {
+        self.abort_search();
         self.position = vec![position.clone()];
     }
 
+    /// Start a new game from `position`. Bots forget what they remembered from the previous game.
+    pub fn new_game(&mut self, position: &Position) {
+        self.set_position(position);
+        self.ponder_move = None;
+
+        for bot in [&mut self.white_bot, &mut self.black_bot].into_iter().flatten() {
+            bot.new_game();
+        }
+    }
+
+    /// Options of the bots, taken from White's bot if both players are bots.
+    pub fn bot_options(&self) -> Vec<BotOption> {
+        self.white_bot.as_ref().or(self.black_bot.as_ref()).map_or(Vec::new(), |bot| bot.options())
+    }
+
+    /// Set an option of every bot. Searches which are already running keep their old options.
+    pub fn set_bot_option(&mut self, name: &str, value: &str) -> Result<(), OptionError> {
+        for bot in [&mut self.white_bot, &mut self.black_bot].into_iter().flatten() {
+            bot.set_option(name, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Make a move and start a search for the next player. If a bot was pondering on this move, its search continues instead.
     pub fn make_move(&mut self, moove: Move) {
+        let ponder_hit = self.ponder.as_ref().is_some_and(|ponder| ponder.expected_move == moove);
+
+        self.push_move(moove);
+
+        if ponder_hit {
+            self.ponder_hit();
+        }
+        else {
+            self.calculate_bot_move();
+        }
+    }
+
+    /// Let bots search on the opponent's time, after their move, for the reply they expect.
+    pub fn set_pondering(&mut self, pondering: bool) {
+        self.pondering = pondering;
+
+        if !pondering && self.ponder.is_some() {
+            self.abort_search();
+        }
+    }
+
+    /// Reply the bot expects to the move of its last search.
+    pub fn ponder_move(&self) -> Option<&Move> {
+        self.ponder_move.as_ref()
+    }
+
+    /// Return `true` if the running search is pondering on the opponent's time.
+    pub fn is_pondering(&self) -> bool {
+        self.ponder.is_some()
+    }
+
+    /// The expected move was played, so the pondering search becomes the bot's real search.
+    fn ponder_hit(&mut self) {
+        if let Some(ponder) = self.ponder.take() {
+            ponder.ponder_hit.trigger();
+        }
+    }
+
+    /// Make a move without starting a bot search for the next player.
+    pub fn push_move(&mut self, moove: Move) {
+        self.update_clock(self.position().current_player());
+
         let mut moved_position = self.position().clone();
 
         moved_position.make_move(moove);
 
         self.position.push(moved_position);
+    }
+
+    /// Time left on the clock of `player`, counting down during their turn. [`None`] without a clock.
+    pub fn remaining_time(&self, player: Player) -> Option<Duration> {
+        let TimeControl::Clock { .. } = self.time_control else {
+            return None;
+        };
+
+        let remaining = self.clocks[clock_index(player)];
+
+        if player == self.position().current_player() {
+            return Some(remaining.saturating_sub(self.turn_start.elapsed()));
+        }
+
+        Some(remaining)
+    }
+
+    /// Charge the time used for the turn to `player` and start the clock for the next turn.
+    fn update_clock(&mut self, player: Player) {
+        let index = clock_index(player);
+        let used = self.turn_start.elapsed();
+
+        self.turn_start = Instant::now();
+        self.moves_made[index] += 1;
+
+        let TimeControl::Clock { remaining, increment, moves_to_go } = &self.time_control else {
+            return;
+        };
+
+        self.clocks[index] = self.clocks[index].saturating_sub(used) + *increment;
+
+        if let Some(moves_to_go) = moves_to_go {
+            if self.moves_made[index] % moves_to_go == 0 {
+                self.clocks[index] += *remaining;
+            }
+        }
+    }
 
-        self.calculate_bot_move();
+    /// Time control for a search of `player`, based on their clock.
+    fn search_time_control(&self, player: Player) -> TimeControl {
+        let index = clock_index(player);
+
+        match &self.time_control {
+            TimeControl::MoveTime(move_time) => TimeControl::MoveTime(*move_time),
+            TimeControl::Clock { increment, moves_to_go, .. } => TimeControl::Clock {
+                remaining: self.clocks[index],
+                increment: *increment,
+                moves_to_go: moves_to_go.map(|n| n - self.moves_made[index] % n),
+            },
+        }
     }
 
     pub fn get_position_n_moves_ago(&self, n: usize) -> Option<&Position> {
@@ -54,9 +213,7 @@ impl Match {
     }
 
     pub fn undo_to_n_moves_ago(&mut self, n: usize) -> &Position {
-        if !self.move_can_be_made() {
-            return self.position();
-        }
+        self.abort_search();
 
         let final_length = self.position.len().saturating_sub(n);
 
@@ -65,68 +222,195 @@ impl Match {
         self.position()
     }
 
+    /// Start a search for the player to move if they are played by a bot, with limits from their clock.
+    /// Otherwise the opponent's bot starts pondering if enabled.
     pub fn calculate_bot_move(&mut self) {
-        if self.position().get_game_state() != GameState::Ongoing {
+        let expected_move = self.ponder_move.take();
+        let limits = SearchLimits::from_time_control(self.search_time_control(self.position().current_player()));
+
+        self.start_search(limits);
+
+        if let Some(expected_move) = expected_move {
+            if self.search.is_none() && self.pondering {
+                self.start_pondering(expected_move);
+            }
+        }
+    }
+
+    /// Start a search with `limits` for the player to move if they are played by a bot. A running search is aborted.
+    pub fn start_search(&mut self, limits: SearchLimits) {
+        self.abort_search();
+
+        self.spawn_search(self.position.clone(), limits);
+    }
+
+    /// Search the position after `expected_move` of the player to move, for the opponent's bot, until the move is
+    /// played or another one aborts the search.
+    fn start_pondering(&mut self, expected_move: Move) {
+        if self.clone_bot(self.position().current_player()).is_some() || !self.position().get_all_legal_moves().contains(&expected_move) {
             return;
         }
 
-        let bot = match self.position.last().unwrap().current_player() {
-            Player::White => {
-                if let Some(bot) = &self.white_bot {
-                    bot
-                }
-                else {
-                    return;
-                }
-            },
-            Player::Black => {
-                if let Some(bot) = &self.black_bot {
-                    bot
-                }
-                else {
-                    return;
-                }
-            }
+        let mut position = self.position().clone();
+        position.make_move(expected_move.clone());
+
+        let ponder_hit = PonderHit::new();
+        let mut limits = SearchLimits::from_time_control(self.search_time_control(position.current_player()));
+        limits.ponder = Some(ponder_hit.clone());
+
+        let mut history = self.position.clone();
+        history.push(position);
+
+        self.spawn_search(history, limits);
+
+        if self.search.is_some() {
+            self.ponder = Some(Ponder { expected_move, ponder_hit });
+        }
+    }
+
+    /// Copy of the bot playing `player`, [`None`] for a human.
+    fn clone_bot(&self, player: Player) -> Option<Box<dyn Bot>> {
+        let bot = match player {
+            Player::White => self.white_bot.as_ref(),
+            Player::Black => self.black_bot.as_ref(),
+        };
+
+        bot.map(|bot| dyn_clone::clone_box(&**bot))
+    }
+
+    /// Search the last position of `history` on another thread if the player to move in it is played by a bot.
+    fn spawn_search(&mut self, history: Vec<Position>, limits: SearchLimits) {
+        let position = history.last().unwrap();
+
+        if position.get_game_state() != GameState::Ongoing {
+            return;
+        }
+
+        let Some(bot) = self.clone_bot(position.current_player()) else {
+            return;
         };
 
-        let pos = self.position().clone();
-        let st = self.search_time.clone();
-        let b = dyn_clone::clone_box(&(**bot));
+        let bot: Arc<dyn Bot> = Arc::from(bot);
+        let search_bot = bot.clone();
+
+        let (sender, receiver) = mpsc::channel();
+        let (event_sender, event_receiver) = mpsc::channel();
+
+        let thread = thread::spawn(move || {
+            let result = search_bot.search(&history, &limits, &event_sender);
+
+            // The receiver is gone if the search was aborted
+            let _ = sender.send(result);
+        });
 
-        self.search_thread = Some(thread::spawn(move || {
-            return b.search_best_move(&pos, st);
-        }));
+        self.search = Some(Search { thread, receiver, bot });
+        self.search_events = Some(event_receiver);
     }
 
-    pub fn move_can_be_made(&self) -> bool {
-        if let Some(thread_handle) = &self.search_thread {
-            thread_handle.is_finished()
+    /// Progress reported by the latest search since the last call.
+    pub fn take_search_events(&mut self) -> Vec<SearchEvent> {
+        match &self.search_events {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => Vec::new(),
         }
-        else {
-            true
+    }
+
+    /// Make the running search finish early. Its best move so far becomes available as usual.
+    pub fn stop_search(&self) {
+        if let Some(search) = &self.search {
+            search.bot.stop();
         }
     }
 
-    pub fn bot_move_available(&self) -> bool {
-        if let Some(thread_handle) = &self.search_thread {
-            thread_handle.is_finished()
+    /// Stop the running search and throw away its result.
+    pub fn abort_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.bot.stop();
+            let _ = search.thread.join();
         }
-        else {
-            false
+
+        self.searched_move = None;
+        self.search_events = None;
+        self.ponder = None;
+    }
+
+    /// Take the search result if the search has finished. A pondering search keeps its result until the ponder hit.
+    fn poll_search(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        if self.ponder.is_some() {
+            return;
+        }
+
+        match search.receiver.try_recv() {
+            Ok(result) => self.finish_search(result),
+            Err(TryRecvError::Empty) => {},
+            // The bot panicked without producing a move
+            Err(TryRecvError::Disconnected) => self.search = None,
+        }
+    }
+
+    fn finish_search(&mut self, result: SearchResult) {
+        if let Some(search) = self.search.take() {
+            let _ = search.thread.join();
         }
+
+        self.ponder_move = result.ponder_move().cloned();
+        self.searched_move = Some(result);
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    pub fn move_can_be_made(&self) -> bool {
+        (self.search.is_none() || self.ponder.is_some()) && self.searched_move.is_none()
+    }
+
+    pub fn bot_move_available(&mut self) -> bool {
+        self.poll_search();
+
+        self.searched_move.is_some()
     }
 
-    pub fn wait_until_calculation_finished(&self) {
-        if let Some(t) = &self.search_thread {
-            while !t.is_finished() { }
+    /// Block until the running search has finished. Returns immediately while pondering, which only ends with a move.
+    pub fn wait_until_calculation_finished(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        if self.ponder.is_some() {
+            return;
+        }
+
+        match search.receiver.recv() {
+            Ok(result) => self.finish_search(result),
+            Err(_) => self.search = None,
         }
     }
 
-    pub fn get_searched_move(&mut self) -> (i32, Move) {
-        if let Some(t) = self.search_thread.take() {
-            return t.join().unwrap().clone();
+    pub fn get_searched_move(&mut self) -> SearchResult {
+        self.wait_until_calculation_finished();
+
+        if let Some(searched_move) = self.searched_move.take() {
+            return searched_move;
         }
 
         panic!("no search thread in progress");
     }
-}
\ No newline at end of file
+}
+
+impl Drop for Match {
+    fn drop(&mut self) {
+        self.abort_search();
+    }
+}
+
+fn clock_index(player: Player) -> usize {
+    match player {
+        Player::White => 0,
+        Player::Black => 1
+    }
+}
@@ -1,9 +1,9 @@
 use std::time::{Duration, Instant};
 
-use crate::{board::{moove::Move, position::Position}, bot::{evaluation_funcs::evaluate_material_and_positioning, iterative_deepening_search::IterativeDeepeningSearch}, r#match::Match};
+use crate::{board::{moove::Move, position::Position}, bot::{evaluation_funcs::evaluate_material_and_positioning, iterative_deepening_search::IterativeDeepeningSearch, time_manager::TimeControl}, r#match::Match};
 
 pub fn performance_test() -> Duration {
-    let mut match_ = Match::new(&Position::default(), Some(Box::new(IterativeDeepeningSearch::new(evaluate_material_and_positioning, true))), Some(Box::new(IterativeDeepeningSearch::new(evaluate_material_and_positioning, true))), Duration::from_secs(10000));
+    let mut match_ = Match::new(&Position::default(), Some(Box::new(IterativeDeepeningSearch::new(evaluate_material_and_positioning, true))), Some(Box::new(IterativeDeepeningSearch::new(evaluate_material_and_positioning, true))), TimeControl::MoveTime(Duration::from_secs(10000)));
 
     let start_time = Instant::now();
 
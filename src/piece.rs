@@ -26,6 +26,18 @@ impl PieceType {
 
 		Ok(e)
 	}
+
+	/// Lowercase letter of the piece type in FEN.
+	pub fn fen_char(&self) -> char {
+		match self {
+			Self::Pawn => 'p',
+			Self::Rook => 'r',
+			Self::Knight => 'n',
+			Self::Bishop => 'b',
+			Self::Queen => 'q',
+			Self::King => 'k'
+		}
+	}
 }
 
 pub trait Piece {
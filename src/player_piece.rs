@@ -23,4 +23,12 @@ impl PlayerPiece {
     pub fn piece(&self) -> PieceType {
         self.piece
     }
+
+    /// Letter of the piece in FEN, uppercase for White.
+    pub fn fen_char(&self) -> char {
+        match self.player {
+            Player::White => self.piece.fen_char().to_ascii_uppercase(),
+            Player::Black => self.piece.fen_char()
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,41 @@
+use crate::{board::{moove::Move, position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{baseline_bots::{AlphaBetaBot, GreedyBot, NegamaxBot, RandomBot}, score::Score, search_limits::SearchLimits, search_observer::NullObserver, search_result::SearchResult, Bot}};
+
+const MATE_IN_ONE_FEN: &str = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
+
+fn search(bot: &dyn Bot, fen: &str) -> SearchResult {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str(fen).unwrap();
+
+	bot.search(&[position], &SearchLimits::default(), &NullObserver)
+}
+
+#[test]
+fn random_bot_plays_legal_moves() {
+	let position = Position::from_fen_str(MATE_IN_ONE_FEN).unwrap();
+
+	for _ in 0..10 {
+		let result = search(&RandomBot, MATE_IN_ONE_FEN);
+
+		assert!(position.get_all_legal_moves().contains(&result.best_move));
+	}
+}
+
+#[test]
+fn greedy_bot_takes_the_most_material() {
+	let result = search(&GreedyBot, "rnb1kbnr/pppp1ppp/8/4p1q1/3P4/2N5/PPP1PPPP/R1BQKBNR w KQkq - 0 3");
+
+	assert_eq!(result.best_move, Move::debug_new_basic("c1", "g5"));
+	assert_eq!(result.score, Score::new(900));
+}
+
+#[test]
+fn fixed_depth_bots_find_mate_in_one() {
+	let alpha_beta = search(&AlphaBetaBot::new(2), MATE_IN_ONE_FEN);
+	let negamax = search(&NegamaxBot::new(2), MATE_IN_ONE_FEN);
+
+	assert_eq!(alpha_beta.best_move, Move::debug_new_basic("h5", "f7"));
+	assert_eq!(alpha_beta.score, Score::mate_in(1));
+	assert_eq!(negamax.best_move, Move::debug_new_basic("h5", "f7"));
+	assert!(negamax.score.is_mate());
+}
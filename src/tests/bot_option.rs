@@ -0,0 +1,16 @@
+use crate::bot::bot_option::{BotOption, OptionError};
+
+#[test]
+fn options_are_announced_in_uci_format() {
+	assert_eq!(BotOption::spin("Hash", 16, 1, 1024, 64).uci_string(), "option name Hash type spin default 16 min 1 max 1024");
+	assert_eq!(BotOption::check("OwnBook", true, false).uci_string(), "option name OwnBook type check default true");
+	assert_eq!(BotOption::combo("Style", "Normal", &["Solid", "Normal"], "Solid").uci_string(), "option name Style type combo default Normal var Solid var Normal");
+	assert_eq!(BotOption::button("Clear Hash").uci_string(), "option name Clear Hash type button");
+}
+
+#[test]
+fn values_outside_the_range_are_rejected() {
+	assert_eq!(BotOption::parse_spin("MultiPV", "3", 1, 256), Ok(3));
+	assert_eq!(BotOption::parse_spin("MultiPV", "0", 1, 256), Err(OptionError::invalid_value("MultiPV", "0")));
+	assert_eq!(BotOption::parse_check("OwnBook", "yes"), Err(OptionError::invalid_value("OwnBook", "yes")));
+}
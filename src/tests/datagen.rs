@@ -0,0 +1,47 @@
+use crate::{board::{position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{eval_params::EvalParams, search_limits::SearchLimits, search_options::SearchOptions}, datagen::{datagen_bot, generate_data, DatagenOptions}};
+
+#[test]
+fn fen_round_trip() {
+	for fen in ["rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "r3k2r/1P6/8/3pP3/8/8/8/R3K2R w Kq d6 0 1", "8/5k2/8/8/8/8/2K5/8 b - - 37 1"] {
+		assert_eq!(Position::from_fen_str(fen).unwrap().to_fen_string(), fen);
+	}
+}
+
+#[test]
+fn generated_data_is_deterministic() {
+	generate_zobrist_numbers();
+
+	let options = DatagenOptions {
+		games: 2,
+		threads: 2,
+		seed: 1,
+		random_plies: 8,
+		limits: SearchLimits {
+			depth: Some(2),
+			..Default::default()
+		},
+	};
+	let new_bot = || datagen_bot(&EvalParams::default(), &SearchOptions::default());
+
+	let mut first = Vec::new();
+	let mut second = Vec::new();
+
+	let positions = generate_data(&options, new_bot, &mut first).unwrap();
+	generate_data(&options, new_bot, &mut second).unwrap();
+
+	assert_eq!(first, second);
+
+	let text = String::from_utf8(first).unwrap();
+
+	assert!(positions > 0);
+	assert_eq!(text.lines().count(), positions);
+
+	for line in text.lines() {
+		let fields: Vec<&str> = line.split(" | ").collect();
+
+		assert_eq!(fields.len(), 3);
+		assert!(Position::from_fen_str(fields[0]).is_ok());
+		assert!(fields[1].parse::<i32>().is_ok());
+		assert!(["1.0", "0.5", "0.0"].contains(&fields[2]));
+	}
+}
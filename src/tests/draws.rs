@@ -0,0 +1,65 @@
+use std::sync::{atomic::AtomicBool, Arc};
+
+use crate::{board::{moove::Move, position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{evaluation_funcs::evaluate_phase_and_bishop_pair_and_rook_open_column, score::Score, search_funcs::iterative_deepening, search_limits::SearchLimits, search_observer::NullObserver, search_options::SearchOptions, transposition_table::TranspositionTable}};
+
+fn search_to_depth(position: &Position, history: &[u64], depth: u32, contempt: i32) -> (Score, Move) {
+	let limits = SearchLimits {
+		depth: Some(depth),
+		..Default::default()
+	};
+	let options = SearchOptions {
+		contempt,
+		..Default::default()
+	};
+
+	let result = iterative_deepening(position, history, None, Arc::new(evaluate_phase_and_bishop_pair_and_rook_open_column), &limits, Arc::new(AtomicBool::new(false)), &NullObserver, Arc::new(TranspositionTable::with_size(1 << 16)), &options).unwrap();
+
+	(result.score, result.best_move)
+}
+
+#[test]
+fn halfmove_clock_resets_on_pawn_moves_and_captures() {
+	let mut position = Position::from_fen_str("4k3/8/8/3p4/8/8/4P3/4K1N1 w - - 12 40").unwrap();
+
+	assert_eq!(position.halfmove_clock(), 12);
+
+	position.make_move(Move::debug_new_basic("g1", "f3"));
+	assert_eq!(position.halfmove_clock(), 13);
+
+	position.make_move(Move::debug_new_basic("e8", "d7"));
+	assert_eq!(position.halfmove_clock(), 14);
+
+	position.make_move(Move::debug_new_basic("e2", "e4"));
+	assert_eq!(position.halfmove_clock(), 0);
+
+	position.make_move(Move::debug_new_basic("d5", "e4"));
+	assert_eq!(position.halfmove_clock(), 0);
+}
+
+#[test]
+fn fifty_move_rule_draws_won_position() {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str("8/8/8/4k3/8/8/8/KQ6 w - - 99 80").unwrap();
+
+	assert_eq!(search_to_depth(&position, &[], 3, 0).0, Score::new(0));
+	assert_eq!(search_to_depth(&position, &[], 3, 25).0, Score::new(-25));
+}
+
+#[test]
+fn losing_side_repeats_for_a_draw() {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str("7k/8/8/8/8/8/8/K2Q4 b - - 10 60").unwrap();
+	let drawing_move = Move::debug_new_basic("h8", "g8");
+
+	let mut repeated_position = position.clone();
+	repeated_position.make_move(drawing_move.clone());
+	let repeated_hash = repeated_position.hash().value();
+
+	// The position after the drawing move already happened twice, two and four plies before it would again
+	let history = [1, repeated_hash, 2, repeated_hash];
+
+	assert_eq!(search_to_depth(&position, &history, 4, 0), (Score::new(0), drawing_move.clone()));
+	assert_eq!(search_to_depth(&position, &history, 4, 30), (Score::new(-30), drawing_move));
+}
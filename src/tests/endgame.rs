@@ -0,0 +1,60 @@
+use crate::{board::{position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{endgame::{mop_up, FULL_SCALE}, eval_params::EvalParams}, player::Player};
+
+fn white_mop_up(fen: &str) -> i32 {
+	return mop_up(&EvalParams::default(), &Position::from_fen_str(fen).unwrap(), Player::White).1;
+}
+
+fn white_scale(fen: &str) -> i32 {
+	return EvalParams::default().endgame_scale(&Position::from_fen_str(fen).unwrap(), 100);
+}
+
+#[test]
+fn mop_up_drives_the_lone_king_to_the_edge() {
+	generate_zobrist_numbers();
+
+	assert!(white_mop_up("7k/8/8/8/8/8/8/Q3K3 w - - 0 1") > white_mop_up("8/8/8/3k4/8/8/8/Q3K3 w - - 0 1"));
+	// Closer kings are better
+	assert!(white_mop_up("7k/8/5K2/8/8/8/8/Q7 w - - 0 1") > white_mop_up("7k/8/8/8/8/8/8/Q3K3 w - - 0 1"));
+	// Only against a lone king
+	assert_eq!(white_mop_up("7k/7p/8/8/8/8/8/Q3K3 w - - 0 1"), 0);
+	assert_eq!(white_mop_up("7k/8/8/8/8/8/P7/4K3 w - - 0 1"), 0);
+}
+
+#[test]
+fn bishop_and_knight_mate_in_the_bishops_corner() {
+	generate_zobrist_numbers();
+
+	// The bishop on c1 covers a1, not h1
+	assert!(white_mop_up("8/8/8/8/8/2K5/8/k1B1N3 w - - 0 1") > white_mop_up("8/8/8/8/8/5K2/8/2B1N2k w - - 0 1"));
+}
+
+#[test]
+fn drawish_material_is_scaled_down() {
+	generate_zobrist_numbers();
+
+	let params = EvalParams::default();
+
+	assert_eq!(white_scale("4k3/5p2/8/2b5/8/3B4/4PPP1/4K3 w - - 0 1"), params.opposite_bishops_scale);
+	assert_eq!(white_scale("4k3/5p2/8/8/2b5/3B4/4PPP1/4K3 w - - 0 1"), FULL_SCALE);
+	assert_eq!(white_scale("4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1"), params.two_knights_scale);
+	assert_eq!(white_scale("4k3/8/8/8/8/8/8/1B2K3 w - - 0 1"), params.lone_minor_scale);
+	assert_eq!(white_scale("4k3/8/8/8/8/8/8/R3K3 w - - 0 1"), FULL_SCALE);
+
+	// A dark-squared bishop can't drive the king out of a8
+	assert_eq!(white_scale("1k6/8/8/P7/8/8/3B4/4K3 w - - 0 1"), params.wrong_rook_pawn_scale);
+	assert_eq!(white_scale("1k6/8/8/P7/8/8/4B3/4K3 w - - 0 1"), FULL_SCALE);
+	assert_eq!(white_scale("8/8/4k3/P7/8/8/3B4/4K3 w - - 0 1"), FULL_SCALE);
+}
+
+#[test]
+fn scaling_only_applies_to_the_player_ahead() {
+	generate_zobrist_numbers();
+
+	let params = EvalParams::default();
+
+	// Black's knight can't win, but White's pawns can
+	let position = Position::from_fen_str("4k3/8/8/2n5/8/8/PPP5/4K3 w - - 0 1").unwrap();
+
+	assert_eq!(params.endgame_scale(&position, 50), FULL_SCALE);
+	assert_eq!(params.endgame_scale(&position, -50), params.lone_minor_scale);
+}
@@ -0,0 +1,63 @@
+use crate::{board::{position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{eval_params::{EvalParams, EvalParamsError}, evaluator::Evaluator}};
+
+#[test]
+fn evaluation_is_symmetric() {
+	generate_zobrist_numbers();
+
+	let params = EvalParams::default();
+
+	// The same position with the colors swapped and the board mirrored
+	let white = Position::from_fen_str("r1bqk2r/pp3ppp/2n2n2/3p4/1b1P4/2N2N2/PP2BPPP/R2QKB1R w KQkq - 0 8").unwrap();
+	let black = Position::from_fen_str("r2qkb1r/pp2bppp/2n2n2/1B1p4/3P4/2N2N2/PP3PPP/R1BQK2R b KQkq - 0 8").unwrap();
+
+	assert_eq!(params.evaluate(&white), params.evaluate(&black));
+	assert_eq!(params.evaluate(&Position::default()), 0);
+}
+
+#[test]
+fn presets_match_evaluation_functions() {
+	generate_zobrist_numbers();
+
+	// Scores of the original evaluate_material_and_positioning, with Black to move and minor pieces and queens off
+	// their starting squares, where mirroring their tables for Black would change the score
+	let expected = [
+		("4k3/8/8/3q4/8/8/3R4/4K3 b - - 0 1", 405),
+		("r1bqk2r/pp3ppp/2n2n2/3p4/1b1P4/2N2N2/PP2BPPP/R2QKB1R b KQkq - 0 8", 5),
+		("r2q1rk1/pb2bppp/1pn1pn2/2pp4/3P4/1PN1PN2/PBQ1BPPP/R4RK1 b - - 0 10", 100),
+		("6k1/5ppp/2n5/1b6/8/2N2q2/5PPP/3Q2K1 b - - 0 30", 310),
+		("8/2k5/3b4/8/4N3/8/5K2/8 b - - 0 50", -25),
+	];
+
+	for (fen, score) in expected {
+		let position = Position::from_fen_str(fen).unwrap();
+
+		assert_eq!(EvalParams::MATERIAL_AND_POSITIONING.evaluate(&position), score, "{}", fen);
+	}
+}
+
+#[test]
+fn parameters_round_trip_through_text() {
+	let mut params = EvalParams::default();
+	params.piece_values[0] = (90, 120);
+	params.piece_square_tables[2][27] = (-7, 13);
+	params.rook_open_file = (30, 5);
+
+	assert_eq!(EvalParams::parse(&params.to_string()).unwrap(), params);
+}
+
+#[test]
+fn missing_parameters_keep_defaults() {
+	let params = EvalParams::parse("# tuned\nbishop_pair 40 60\nknight_value 300 280 # no comment\n").unwrap();
+
+	assert_eq!(params.bishop_pair, (40, 60));
+	assert_eq!(params.piece_values[2], (300, 280));
+	assert_eq!(params.piece_square_tables, EvalParams::default().piece_square_tables);
+}
+
+#[test]
+fn invalid_parameters_are_rejected() {
+	assert!(matches!(EvalParams::parse("queen_vaule 900 900"), Err(EvalParamsError::UnknownParameter(_))));
+	assert!(matches!(EvalParams::parse("bishop_pair 40"), Err(EvalParamsError::WrongValueCount { expected: 2, found: 1, .. })));
+	assert!(matches!(EvalParams::parse("12 bishop_pair 40 60"), Err(EvalParamsError::MissingName(_))));
+	assert!(matches!(EvalParams::parse("rook_open_file 4.5 3"), Err(EvalParamsError::InvalidNumber(_))));
+}
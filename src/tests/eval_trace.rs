@@ -0,0 +1,42 @@
+use crate::{board::{position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{eval_params::EvalParams, eval_trace::EvalTrace, evaluator::Evaluator}};
+
+#[test]
+fn trace_adds_up_to_the_evaluation() {
+	generate_zobrist_numbers();
+
+	let fens = [
+		"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+		"r1bq1rk1/pp3ppp/2n2n2/3p4/1b1P4/2N2N2/PP2BPPP/R2QK2R b KQ - 0 8",
+		"6k1/1R3ppp/8/3P4/8/2b5/5PPP/6K1 w - - 0 30",
+		"8/8/4k3/8/2P5/8/3K4/8 b - - 0 50",
+		"4k3/5p2/8/2b5/8/3B4/4PPP1/4K3 w - - 0 40",
+		"7k/8/8/8/8/8/8/Q3K3 b - - 0 60",
+	];
+
+	for fen in fens {
+		let position = Position::from_fen_str(fen).unwrap();
+
+		for params in [EvalParams::default(), EvalParams::PAWN_STRUCTURE, EvalParams::MATERIAL_AND_POSITIONING_BY_PHASE] {
+			assert_eq!(EvalTrace::new(&params, &position).total(), params.evaluate(&position), "{}", fen);
+		}
+	}
+}
+
+#[test]
+fn mirrored_position_swaps_the_sides() {
+	generate_zobrist_numbers();
+
+	let white = Position::from_fen_str("r1bqk2r/pp3ppp/2n2n2/3p4/1b1P4/2N2N2/PP2BPPP/R2QKB1R w KQkq - 0 8").unwrap();
+	let black = Position::from_fen_str("r2qkb1r/pp2bppp/2n2n2/1B1p4/3P4/2N2N2/PP3PPP/R1BQK2R b KQkq - 0 8").unwrap();
+
+	let params = EvalParams::default();
+	let white_trace = EvalTrace::new(&params, &white);
+	let black_trace = EvalTrace::new(&params, &black);
+
+	for (w, b) in white_trace.terms.iter().zip(&black_trace.terms) {
+		assert_eq!((w.white, w.black), (b.black, b.white), "{}", w.name);
+	}
+
+	// A header, the terms, the total and two summary lines
+	assert_eq!(white_trace.lines().len(), white_trace.terms.len() + 4);
+}
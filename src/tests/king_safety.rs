@@ -0,0 +1,53 @@
+use crate::{board::{position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{eval_params::EvalParams, evaluator::Evaluator, king_safety::evaluate_king_safety}};
+
+fn king_safety(fen: &str) -> (i32, i32) {
+	return evaluate_king_safety(&EvalParams::default(), &Position::from_fen_str(fen).unwrap());
+}
+
+#[test]
+fn pawn_shield_is_rewarded() {
+	generate_zobrist_numbers();
+
+	let sheltered = king_safety("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1");
+	// White's g-pawn has left, opening the file in front of the king
+	let broken = king_safety("6k1/5ppp/8/8/8/8/5P1P/6K1 w - - 0 1");
+
+	assert_eq!(sheltered, (0, 0));
+	assert!(broken.0 < sheltered.0);
+}
+
+#[test]
+fn attacks_on_the_king_are_penalised() {
+	generate_zobrist_numbers();
+
+	let quiet = king_safety("r5k1/5ppp/8/8/8/2N5/5PPP/3Q2K1 w - - 0 1");
+	// The queen and the knight both reach the squares around Black's king
+	let attacked = king_safety("r5k1/5ppp/8/5N1Q/8/8/5PPP/6K1 w - - 0 1");
+	let attacked_by_one = king_safety("r5k1/5ppp/8/5N2/8/8/5PPP/3Q2K1 w - - 0 1");
+
+	assert!(attacked.0 > quiet.0);
+	assert_eq!(attacked_by_one, quiet);
+	// Fades out in the endgame
+	assert_eq!(attacked.1, quiet.1);
+}
+
+#[test]
+fn king_safety_is_symmetric() {
+	generate_zobrist_numbers();
+
+	let params = EvalParams::default();
+
+	for (fen, mirrored) in [
+		("r4rk1/pp3p1p/2n3p1/3q4/3P4/2PB1N2/P4PPP/R2Q1RK1 w - - 0 1", "r2q1rk1/p4ppp/2pb1n2/3p4/3Q4/2N3P1/PP3P1P/R4RK1 b - - 0 1"),
+		("2kr3r/ppp2ppp/8/8/4q3/6n1/PP3PPP/1K1R3R b - - 0 1", "1k1r3r/pp3ppp/6N1/4Q3/8/8/PPP2PPP/2KR3R w - - 0 1"),
+	] {
+		let position = Position::from_fen_str(fen).unwrap();
+		let mirrored = Position::from_fen_str(mirrored).unwrap();
+
+		let score = evaluate_king_safety(&params, &position);
+		let mirrored_score = evaluate_king_safety(&params, &mirrored);
+
+		assert_eq!(score, (-mirrored_score.0, -mirrored_score.1));
+		assert_eq!(params.evaluate(&position), params.evaluate(&mirrored));
+	}
+}
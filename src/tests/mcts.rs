@@ -0,0 +1,86 @@
+use crate::{board::{moove::Move, position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{evaluation_funcs::evaluate_phase_and_bishop_pair_and_rook_open_column, mcts::MctsBot, score::Score, search_limits::SearchLimits, search_observer::NullObserver, Bot}};
+
+fn playouts(nodes: u64) -> SearchLimits {
+	SearchLimits {
+		nodes: Some(nodes),
+		..Default::default()
+	}
+}
+
+#[test]
+fn finds_mate_in_one() {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4").unwrap();
+	let result = MctsBot::new(evaluate_phase_and_bishop_pair_and_rook_open_column).search(&[position], &playouts(2000), &NullObserver);
+
+	assert_eq!(result.best_move, Move::debug_new_basic("h5", "f7"));
+	assert_eq!(result.score, Score::mate_in(1));
+}
+
+#[test]
+fn takes_hanging_queen_with_threads_and_rollouts() {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str("rnb1kbnr/pppp1ppp/8/4p1q1/3P4/2N5/PPP1PPPP/R1BQKBNR w KQkq - 0 3").unwrap();
+	let mut bot = MctsBot::new(evaluate_phase_and_bishop_pair_and_rook_open_column);
+
+	bot.set_option("Threads", "2").unwrap();
+	bot.set_option("Rollout Depth", "2").unwrap();
+
+	let result = bot.search(&[position], &playouts(2000), &NullObserver);
+
+	assert_eq!(result.best_move, Move::debug_new_basic("c1", "g5"));
+}
+
+#[test]
+fn tree_is_reused_after_the_expected_reply() {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str("rnb1kbnr/pppp1ppp/8/4p1q1/3P4/2N5/PPP1PPPP/R1BQKBNR w KQkq - 0 3").unwrap();
+	let bot = MctsBot::new(evaluate_phase_and_bishop_pair_and_rook_open_column);
+	let result = bot.search(&[position.clone()], &playouts(3000), &NullObserver);
+
+	let mut after_move = position.clone();
+	after_move.make_move(result.best_move.clone());
+	let mut after_reply = after_move.clone();
+	after_reply.make_move(result.ponder_move().unwrap().clone());
+
+	// The reused part of the tree already has a line longer than a handful of new playouts could build
+	let reused = bot.search(&[position, after_move, after_reply], &playouts(1), &NullObserver);
+
+	assert!(reused.stats.depth > 1);
+	assert!(reused.stats.seldepth >= reused.stats.depth);
+}
+
+#[test]
+fn full_tree_keeps_searching() {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str("rnb1kbnr/pppp1ppp/8/4p1q1/3P4/2N5/PPP1PPPP/R1BQKBNR w KQkq - 0 3").unwrap();
+	let mut bot = MctsBot::new(evaluate_phase_and_bishop_pair_and_rook_open_column);
+
+	// Full after a few dozen expansions
+	bot.set_option("Tree Size", "1").unwrap();
+
+	let result = bot.search(&[position], &playouts(3000), &NullObserver);
+
+	assert_eq!(result.stats.nodes, 3000);
+	assert_eq!(result.best_move, Move::debug_new_basic("c1", "g5"));
+}
+
+#[test]
+fn depth_limit_ends_the_search() {
+	generate_zobrist_numbers();
+
+	let limits = SearchLimits {
+		depth: Some(2),
+		..Default::default()
+	};
+
+	let bot = MctsBot::new(evaluate_phase_and_bishop_pair_and_rook_open_column);
+	let result = bot.search(&[Position::default()], &limits, &NullObserver);
+
+	assert!(result.stats.depth >= 2);
+	assert!(result.pv.len() >= 2);
+}
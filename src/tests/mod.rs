@@ -28,6 +28,46 @@ mod slider_collision;
 mod integrity_tests;
 #[cfg(test)]
 mod mailbox_integrity;
+#[cfg(test)]
+mod transposition;
+#[cfg(test)]
+mod search;
+#[cfg(test)]
+mod score;
+#[cfg(test)]
+mod time_manager;
+#[cfg(test)]
+mod uci;
+#[cfg(test)]
+mod draws;
+#[cfg(test)]
+mod bot_option;
+#[cfg(test)]
+mod strength;
+#[cfg(test)]
+mod baseline_bots;
+#[cfg(test)]
+mod mcts;
+#[cfg(test)]
+mod eval_params;
+#[cfg(test)]
+mod pawn_structure;
+#[cfg(test)]
+mod king_safety;
+#[cfg(test)]
+mod piece_activity;
+#[cfg(test)]
+mod piece_square_score;
+#[cfg(test)]
+mod nnue;
+#[cfg(test)]
+mod datagen;
+#[cfg(test)]
+mod tuner;
+#[cfg(test)]
+mod eval_trace;
+#[cfg(test)]
+mod endgame;
 
 
 #[cfg(test)]
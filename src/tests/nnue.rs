@@ -0,0 +1,106 @@
+use crate::{board::{moove::Move, position::Position, zobrist_hash::generate_zobrist_numbers}, bot::nnue::{Accumulator, Network, NnueError, HIDDEN_SIZE, INPUT_SIZE}};
+
+/// Network with small pseudo-random weights.
+fn test_network() -> Network {
+	let mut bytes = b"CNUE".to_vec();
+	let mut state: u32 = 12345;
+
+	bytes.extend((HIDDEN_SIZE as u32).to_le_bytes());
+
+	for _ in 0..(INPUT_SIZE + 3) * HIDDEN_SIZE {
+		state = state.wrapping_mul(1103515245).wrapping_add(12345);
+
+		bytes.extend((((state >> 16) % 64) as i16 - 32).to_le_bytes());
+	}
+
+	bytes.extend(1000i32.to_le_bytes());
+
+	return Network::from_bytes(&bytes).unwrap();
+}
+
+/// Network with every weight at its maximum, whose sums overflow.
+fn saturated_network() -> Network {
+	let mut bytes = b"CNUE".to_vec();
+
+	bytes.extend((HIDDEN_SIZE as u32).to_le_bytes());
+
+	for _ in 0..(INPUT_SIZE + 3) * HIDDEN_SIZE {
+		bytes.extend(i16::MAX.to_le_bytes());
+	}
+
+	bytes.extend(i32::MAX.to_le_bytes());
+
+	return Network::from_bytes(&bytes).unwrap();
+}
+
+fn find_move(position: &Position, uci: &str) -> Move {
+	return position.get_all_legal_moves().into_iter().find(|m| m.uci_string() == uci).unwrap();
+}
+
+#[test]
+fn network_file_round_trip() {
+	let network = test_network();
+	let bytes = network.to_bytes();
+
+	assert_eq!(Network::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+	assert!(matches!(Network::from_bytes(&bytes[..bytes.len() - 2]), Err(NnueError::WrongLength { .. })));
+	assert!(matches!(Network::from_bytes(b"NOPE"), Err(NnueError::InvalidHeader)));
+}
+
+#[test]
+fn accumulator_is_updated_incrementally() {
+	generate_zobrist_numbers();
+
+	let network = test_network();
+	let mut position = Position::from_fen_str("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+	let mut accumulator = Accumulator::new(&network, position.board());
+
+	// En passant, castling on both sides, a king move, a capturing promotion and a capture by a rook
+	for uci in ["e5d6", "e8g8", "e1c1", "g8g7", "b7a8q", "f8a8"] {
+		let moove = find_move(&position, uci);
+		let moved_piece = position.get_piece(moove.from_position()).unwrap();
+
+		match moove.clone() {
+			Move::Basic(basic_move) => accumulator.update_basic_move(&network, basic_move.clone(), moved_piece, position.get_piece(basic_move.to_position())),
+			Move::Castling(castling_move) => accumulator.update_castling_move(&network, castling_move, moved_piece),
+			Move::EnPassant(en_passant_move) => accumulator.update_en_passant_move(&network, en_passant_move.clone(), moved_piece, position.get_piece(en_passant_move.captured_tile()).unwrap()),
+			Move::Promoting(promoting_move) => accumulator.update_promoting_move(&network, promoting_move.clone(), moved_piece, position.get_piece(promoting_move.to_position())),
+		}
+
+		position.make_move(moove);
+
+		assert!(accumulator == Accumulator::new(&network, position.board()));
+	}
+}
+
+#[test]
+fn evaluation_is_symmetric() {
+	generate_zobrist_numbers();
+
+	let network = test_network();
+	let position = Position::from_fen_str("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4").unwrap();
+	let mirrored = Position::from_fen_str("rnb1k1nr/pppp1ppp/8/2b1p3/4P2q/2N2N2/PPPP1PPP/R1BQKB1R b KQkq - 4 4").unwrap();
+
+	assert_eq!(network.evaluate(&position), network.evaluate(&mirrored));
+}
+
+#[test]
+fn large_weights_do_not_overflow() {
+	generate_zobrist_numbers();
+
+	let network = saturated_network();
+	let mut position = Position::from_fen_str("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4").unwrap();
+	let mut accumulator = Accumulator::new(&network, position.board());
+
+	let moove = find_move(&position, "h5f7");
+	let moved_piece = position.get_piece(moove.from_position()).unwrap();
+
+	if let Move::Basic(basic_move) = moove.clone() {
+		accumulator.update_basic_move(&network, basic_move.clone(), moved_piece, position.get_piece(basic_move.to_position()));
+	}
+
+	position.make_move(moove);
+
+	assert!(accumulator == Accumulator::new(&network, position.board()));
+	assert!(network.evaluate(&position) > 0);
+}
@@ -0,0 +1,63 @@
+use crate::{board::{bitboard::Bitboard, position::Position, zobrist_hash::{generate_zobrist_numbers, ZobristHash}}, bot::{eval_params::EvalParams, evaluator::{ClassicalEvaluator, Evaluator}, pawn_structure::PawnStructure}};
+
+fn play(position: &mut Position, uci: &str) {
+	let moove = position.get_all_legal_moves().into_iter().find(|m| m.uci_string() == uci).unwrap();
+
+	position.make_move(moove);
+}
+
+#[test]
+fn pawn_hash_is_updated_incrementally() {
+	generate_zobrist_numbers();
+
+	let mut position = Position::from_fen_str("r3k3/1P6/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+	// En passant, a king move, a capturing promotion and a king capturing a pawn
+	for uci in ["e5d6", "e8d7", "b7a8q", "d7d6"] {
+		play(&mut position, uci);
+
+		assert_eq!(position.hash().pawn_value(), ZobristHash::from_position(&position).pawn_value());
+	}
+
+	assert_eq!(position.hash().pawn_value(), 0);
+}
+
+#[test]
+fn finds_passed_pawns() {
+	generate_zobrist_numbers();
+
+	// a2 is doubled, a3 and b5 are passed for White and d3 for Black, c4 is blocked by c5
+	let position = Position::from_fen_str("4k3/8/8/1Pp5/2P5/P2p4/P7/4K3 w - - 0 1").unwrap();
+	let structure = PawnStructure::new(&EvalParams::default(), &position);
+
+	assert_eq!(structure.passed_pawns, Bitboard(1 << 16 | 1 << 33 | 1 << 19));
+}
+
+#[test]
+fn weak_pawns_are_penalised() {
+	generate_zobrist_numbers();
+
+	let params = EvalParams::default();
+
+	// The same material, with doubled and isolated pawns for White
+	let weak = Position::from_fen_str("4k3/pp3ppp/8/8/8/8/P1P2PPP/2P1K3 w - - 0 1").unwrap();
+	let healthy = Position::from_fen_str("4k3/pp3ppp/8/8/8/8/PPP2PPP/4K3 w - - 0 1").unwrap();
+
+	assert!(PawnStructure::new(&params, &weak).score.1 < PawnStructure::new(&params, &healthy).score.1);
+}
+
+#[test]
+fn cached_evaluation_matches_uncached() {
+	generate_zobrist_numbers();
+
+	let params = EvalParams::default();
+	let evaluator = ClassicalEvaluator::new(params.clone());
+
+	for fen in ["4k3/8/8/1Pp5/2P5/P2p4/P7/4K3 w - - 0 1", "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4", "8/5k2/8/8/8/8/2K5/8 b - - 0 1"] {
+		let position = Position::from_fen_str(fen).unwrap();
+
+		// The second evaluation hits the table
+		assert_eq!(evaluator.evaluate(&position), params.evaluate(&position));
+		assert_eq!(evaluator.evaluate(&position), params.evaluate(&position));
+	}
+}
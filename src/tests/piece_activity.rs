@@ -0,0 +1,52 @@
+use crate::{board::{position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{eval_params::EvalParams, evaluator::Evaluator, piece_activity::{evaluate_piece_activity, mobility}}, player::Player};
+
+fn piece_activity(fen: &str) -> (i32, i32) {
+	return evaluate_piece_activity(&EvalParams::default(), &Position::from_fen_str(fen).unwrap());
+}
+
+#[test]
+fn mobility_skips_squares_guarded_by_pawns() {
+	generate_zobrist_numbers();
+
+	// The knight's squares c6 and e6 are guarded by the d7 pawn, b5 by the a6 pawn
+	let position = Position::from_fen_str("4k3/3p4/p7/8/3N4/8/8/4K3 w - - 0 1").unwrap();
+
+	assert_eq!(mobility(&position, Player::White), 5);
+}
+
+#[test]
+fn outposts_and_trapped_pieces() {
+	generate_zobrist_numbers();
+
+	// The knight on d5 is defended by e4 and no black pawn can attack it
+	let outpost = piece_activity("4k3/pp3ppp/8/3N4/4P3/8/PP3PPP/4K3 w - - 0 1");
+	let chased = piece_activity("4k3/pp2p1pp/8/3N4/4P3/8/PP3PPP/4K3 w - - 0 1");
+
+	assert!(outpost.0 > chased.0);
+
+	let free_bishop = piece_activity("4k3/1p3ppp/p7/8/8/8/1B3PPP/4K3 w - - 0 1");
+	let trapped_bishop = piece_activity("4k3/Bp3ppp/1p6/8/8/8/5PPP/4K3 w - - 0 1");
+
+	assert!(trapped_bishop.0 < free_bishop.0 - 50);
+}
+
+#[test]
+fn piece_activity_is_symmetric() {
+	generate_zobrist_numbers();
+
+	let params = EvalParams::default();
+
+	for (fen, mirrored) in [
+		("r1bqk2r/pppp1ppp/2n2n2/2b1p3/2B1P3/3P1N2/PPP2PPP/RNBQK2R w KQkq - 0 1", "rnbqk2r/ppp2ppp/3p1n2/2b1p3/2B1P3/2N2N2/PPPP1PPP/R1BQK2R b KQkq - 0 1"),
+		("5rk1/1R4pp/8/3N4/4P3/8/6PP/5RK1 w - - 0 1", "5rk1/6pp/8/4p3/3n4/8/1r4PP/5RK1 b - - 0 1"),
+	] {
+		let position = Position::from_fen_str(fen).unwrap();
+		let mirrored = Position::from_fen_str(mirrored).unwrap();
+
+		let score = evaluate_piece_activity(&params, &position);
+		let mirrored_score = evaluate_piece_activity(&params, &mirrored);
+
+		assert_eq!(score, (-mirrored_score.0, -mirrored_score.1));
+		assert_eq!(params.evaluate(&position), params.evaluate(&mirrored));
+	}
+}
@@ -0,0 +1,56 @@
+use crate::{board::{piece_square_score::PieceSquareScore, position::Position, zobrist_hash::generate_zobrist_numbers}, bot::eval_params::EvalParams};
+
+fn play(position: &mut Position, uci: &str) {
+	let moove = position.get_all_legal_moves().into_iter().find(|m| m.uci_string() == uci).unwrap();
+
+	position.make_move(moove);
+}
+
+#[test]
+fn scores_are_updated_incrementally() {
+	generate_zobrist_numbers();
+
+	let mut position = Position::from_fen_str("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+
+	// En passant, castling on both sides, a capturing promotion and a capture by a rook
+	for uci in ["e5d6", "e8g8", "e1c1", "g8g7", "b7a8q", "f8a8"] {
+		play(&mut position, uci);
+
+		assert_eq!(*position.piece_square_score(), PieceSquareScore::from_board(position.board()));
+	}
+}
+
+#[test]
+fn evaluation_with_position_scores_matches() {
+	generate_zobrist_numbers();
+
+	let params = EvalParams::default();
+
+	assert!(params.uses_position_scores());
+	assert!(!EvalParams::MATERIAL_AND_POSITIONING.uses_position_scores());
+
+	for fen in ["r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4", "8/5k2/3r4/8/2P5/8/2K5/8 b - - 0 1"] {
+		let position = Position::from_fen_str(fen).unwrap();
+
+		assert_eq!(params.evaluate_with_pawn_table(&position, None, true), params.evaluate_with_pawn_table(&position, None, false));
+	}
+}
+
+#[test]
+fn equality_ignores_move_counters_and_scores() {
+	generate_zobrist_numbers();
+
+	let mut position = Position::default();
+
+	for uci in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+		play(&mut position, uci);
+	}
+
+	assert_eq!(position.halfmove_clock(), 4);
+	assert!(position == Position::default());
+	assert!(position == Position::from_fen_str("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 4 3").unwrap());
+
+	play(&mut position, "e2e4");
+
+	assert!(position != Position::default());
+}
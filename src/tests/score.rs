@@ -0,0 +1,29 @@
+use crate::bot::score::Score;
+
+#[test]
+fn mates_are_formatted_in_moves() {
+	assert_eq!(Score::mate_in(1).to_string(), "M1");
+	assert_eq!(Score::mate_in(9).to_string(), "M5");
+	assert_eq!(Score::mated_in(6).to_string(), "-M3");
+	assert_eq!(Score::new(-35).to_string(), "-35");
+}
+
+#[test]
+fn shorter_mates_score_higher() {
+	assert!(Score::mate_in(1) > Score::mate_in(3));
+	assert!(Score::mated_in(2) < Score::mated_in(4));
+	assert!(Score::mate_in(99) > Score::new(5000));
+	assert!(!Score::new(5000).is_mate());
+}
+
+#[test]
+fn transposition_adjustment_keeps_distance_to_mate() {
+	// Mate found 5 plies below a position stored at ply 3 is a mate at ply 8 from the root
+	let stored = Score::mate_in(8).to_transposition(3);
+
+	assert_eq!(Score::from_transposition(stored, 3), Score::mate_in(8));
+	// The same position reached at ply 1 mates 2 plies sooner
+	assert_eq!(Score::from_transposition(stored, 1), Score::mate_in(6));
+	assert_eq!(Score::from_transposition(Score::mated_in(4).to_transposition(2), 6), Score::mated_in(8));
+	assert_eq!(Score::from_transposition(Score::new(-250).to_transposition(2), 6), Score::new(-250));
+}
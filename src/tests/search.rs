@@ -0,0 +1,218 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, mpsc, Arc}, thread, time::{Duration, Instant}};
+
+use crate::{board::{moove::Move, position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{evaluation_funcs::evaluate_phase_and_bishop_pair_and_rook_open_column, score::Score, search_funcs::{can_extend, iterative_deepening, last_capture_square}, search_limits::{PonderHit, SearchLimits}, search_observer::{NullObserver, SearchEvent}, search_options::SearchOptions, transposition_table::TranspositionTable}};
+
+const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+fn search_with_limits(fen: &str, limits: &SearchLimits, stop: Arc<AtomicBool>, options: &SearchOptions) -> (Score, Move) {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str(fen).unwrap();
+	let transposition_table = Arc::new(TranspositionTable::with_size(1 << 16));
+
+	let result = iterative_deepening(&position, &[], None, Arc::new(evaluate_phase_and_bishop_pair_and_rook_open_column), limits, stop, &NullObserver, transposition_table, options).unwrap();
+
+	(result.score, result.best_move)
+}
+
+fn search(fen: &str, options: &SearchOptions) -> (Score, Move) {
+	search_with_limits(fen, &SearchLimits::move_time(Duration::from_millis(500)), Arc::new(AtomicBool::new(false)), options)
+}
+
+#[test]
+fn finds_mate_in_one() {
+	let (eval, best_move) = search("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4", &SearchOptions::default());
+
+	assert_eq!(best_move, Move::debug_new_basic("h5", "f7"));
+	assert_eq!(eval, Score::mate_in(1));
+}
+
+#[test]
+fn evaluation_noise_keeps_mates() {
+	let options = SearchOptions {
+		eval_noise: 200,
+		..Default::default()
+	};
+
+	let (eval, best_move) = search("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4", &options);
+
+	assert_eq!(best_move, Move::debug_new_basic("h5", "f7"));
+	assert_eq!(eval, Score::mate_in(1));
+}
+
+#[test]
+fn takes_hanging_queen_with_and_without_aspiration_windows() {
+	let mut options = SearchOptions::default();
+
+	for aspiration_windows in [true, false] {
+		options.aspiration_windows = aspiration_windows;
+
+		let (_, best_move) = search("rnb1kbnr/pppp1ppp/8/4p1q1/3P4/2N5/PPP1PPPP/R1BQKBNR w KQkq - 0 3", &options);
+
+		assert_eq!(best_move, Move::debug_new_basic("c1", "g5"));
+	}
+}
+
+#[test]
+fn depth_and_node_limits_end_the_search() {
+	let depth_limit = SearchLimits { depth: Some(3), ..Default::default() };
+	let node_limit = SearchLimits { nodes: Some(20000), ..Default::default() };
+
+	for limits in [depth_limit, node_limit] {
+		let start = Instant::now();
+
+		search_with_limits(START_FEN, &limits, Arc::new(AtomicBool::new(false)), &SearchOptions::default());
+
+		assert!(start.elapsed() < Duration::from_secs(10));
+	}
+}
+
+#[test]
+fn stop_signal_ends_infinite_search() {
+	let stop = Arc::new(AtomicBool::new(false));
+
+	let search_stop = stop.clone();
+	let search_thread = thread::spawn(move || search_with_limits(START_FEN, &SearchLimits { infinite: true, ..Default::default() }, search_stop, &SearchOptions::default()));
+
+	thread::sleep(Duration::from_millis(200));
+	stop.store(true, Ordering::Relaxed);
+
+	let start = Instant::now();
+	let (_, best_move) = search_thread.join().unwrap();
+
+	assert!(start.elapsed() < Duration::from_secs(1));
+	assert!(Position::from_fen_str(START_FEN).unwrap().get_all_legal_moves().contains(&best_move));
+}
+
+#[test]
+fn pondering_search_starts_its_clock_at_ponder_hit() {
+	let ponder_hit = PonderHit::new();
+	let limits = SearchLimits {
+		ponder: Some(ponder_hit.clone()),
+		..SearchLimits::move_time(Duration::from_millis(100))
+	};
+
+	let search_thread = thread::spawn(move || search_with_limits(START_FEN, &limits, Arc::new(AtomicBool::new(false)), &SearchOptions::default()));
+
+	thread::sleep(Duration::from_millis(400));
+	assert!(!search_thread.is_finished());
+
+	ponder_hit.trigger();
+
+	let start = Instant::now();
+	search_thread.join().unwrap();
+
+	assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn reports_every_iteration_with_principal_variation() {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4").unwrap();
+	let transposition_table = Arc::new(TranspositionTable::with_size(1 << 16));
+	let (sender, receiver) = mpsc::channel();
+	let limits = SearchLimits {
+		depth: Some(3),
+		..Default::default()
+	};
+
+	let result = iterative_deepening(&position, &[], None, Arc::new(evaluate_phase_and_bishop_pair_and_rook_open_column), &limits, Arc::new(AtomicBool::new(false)), &sender, transposition_table, &SearchOptions::default()).unwrap();
+
+	let infos: Vec<_> = receiver.try_iter().map(|event| match event {
+		SearchEvent::Iteration(info) => info,
+		SearchEvent::BookMove(_) => panic!("no book is used"),
+	}).collect();
+
+	let last = infos.last().unwrap();
+
+	assert!(infos.windows(2).all(|w| w[0].depth < w[1].depth));
+	assert!(infos.iter().all(|info| info.nodes > 0 && info.seldepth >= info.depth));
+	assert_eq!(last.score, result.score);
+	assert_eq!(last.pv.first(), Some(&result.best_move));
+	assert_eq!(result.pv.first(), Some(&result.best_move));
+	assert_eq!(result.stats.depth, last.depth);
+	assert_eq!(result.stats.nodes, last.nodes);
+}
+
+#[test]
+fn multi_pv_reports_distinct_lines_best_first() {
+	generate_zobrist_numbers();
+
+	let position = Position::from_fen_str("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3").unwrap();
+	let transposition_table = Arc::new(TranspositionTable::with_size(1 << 16));
+	let (sender, receiver) = mpsc::channel();
+	let limits = SearchLimits {
+		depth: Some(3),
+		..Default::default()
+	};
+	let options = SearchOptions {
+		multi_pv: 3,
+		..Default::default()
+	};
+
+	let result = iterative_deepening(&position, &[], None, Arc::new(evaluate_phase_and_bishop_pair_and_rook_open_column), &limits, Arc::new(AtomicBool::new(false)), &sender, transposition_table, &options).unwrap();
+
+	let infos: Vec<_> = receiver.try_iter().filter_map(|event| match event {
+		SearchEvent::Iteration(info) => Some(info),
+		SearchEvent::BookMove(_) => None,
+	}).collect();
+
+	let last_iteration = &infos[infos.len() - 3..];
+
+	assert_eq!(infos.len() % 3, 0);
+	assert_eq!(last_iteration.iter().map(|info| info.multi_pv).collect::<Vec<_>>(), vec![1, 2, 3]);
+	assert_eq!(last_iteration[0].score, result.score);
+	assert_eq!(last_iteration[0].pv[0], result.best_move);
+	assert_ne!(last_iteration[0].pv[0], last_iteration[1].pv[0]);
+	assert_ne!(last_iteration[1].pv[0], last_iteration[2].pv[0]);
+	assert_ne!(last_iteration[0].pv[0], last_iteration[2].pv[0]);
+	assert!(last_iteration[0].score >= last_iteration[1].score && last_iteration[1].score >= last_iteration[2].score);
+}
+
+#[test]
+fn finished_game_is_not_searched() {
+	generate_zobrist_numbers();
+
+	let options = SearchOptions {
+		multi_pv: 3,
+		..Default::default()
+	};
+
+	// Mated and stalemated
+	for fen in ["rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3", "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"] {
+		let position = Position::from_fen_str(fen).unwrap();
+		let transposition_table = Arc::new(TranspositionTable::with_size(1 << 16));
+
+		let result = iterative_deepening(&position, &[], None, Arc::new(evaluate_phase_and_bishop_pair_and_rook_open_column), &SearchLimits::move_time(Duration::from_millis(100)), Arc::new(AtomicBool::new(false)), &NullObserver, transposition_table, &options);
+
+		assert_eq!(result, None);
+	}
+}
+
+#[test]
+fn extensions_are_limited_by_root_depth() {
+	assert!(!can_extend(1, 0, 0));
+	assert!(can_extend(2, 1, 0));
+	assert!(!can_extend(2, 1, 1));
+	assert!(can_extend(6, 5, 2));
+	assert!(!can_extend(6, 5, 3));
+	assert!(!can_extend(6, 12, 0));
+}
+
+#[test]
+fn last_capture_is_found_from_the_game_history() {
+	generate_zobrist_numbers();
+
+	let start = Position::from_fen_str("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2").unwrap();
+
+	let mut capture = start.clone();
+	capture.make_move(Move::debug_new_basic("e4", "d5"));
+
+	let mut quiet = capture.clone();
+	quiet.make_move(Move::debug_new_basic("g8", "f6"));
+
+	assert_eq!(last_capture_square(&[start.clone(), capture.clone()]), Some(Move::debug_new_basic("e4", "d5").to_position()));
+	assert_eq!(last_capture_square(&[start, capture.clone(), quiet]), None);
+	assert_eq!(last_capture_square(&[capture]), None);
+}
@@ -210,4 +210,25 @@ fn white_is_starting_player() {
     let position = Position::default();
 
     assert_eq!(position.current_player(), Player::White);
-}
\ No newline at end of file
+}
+
+#[test]
+fn start_has_full_endgame_material() {
+    let position = Position::default();
+
+    // 8 pawns, 2 rooks, 2 bishops, 2 knights and a queen at their endgame values
+    let material = 8 * 144 + 2 * 803 + 2 * 510 + 2 * 475 + 1623;
+
+    assert_eq!(position.board().get_phase_material_for_player(Player::White, (0, 100)), material);
+    assert_eq!(position.board().get_phase_material_for_player(Player::Black, (0, 100)), material);
+}
+
+#[test]
+fn start_has_one_queen_each() {
+    let position = Position::default();
+    let queens = *position.board().get_piece_bitboard(PieceType::Queen);
+
+    assert_eq!(queens.0.count_ones(), 2);
+    assert_eq!((queens & *position.board().get_player_bitboard(Player::White)).0.count_ones(), 1);
+    assert_eq!((queens & *position.board().get_piece_bitboard(PieceType::King)).0.count_ones(), 0);
+}
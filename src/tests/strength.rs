@@ -0,0 +1,10 @@
+use crate::bot::limited_strength::{Strength, MAX_SKILL_LEVEL};
+
+#[test]
+fn elo_maps_onto_skill_levels() {
+	assert_eq!(Strength::Elo(800).skill_level(), 0);
+	assert_eq!(Strength::Elo(1850).skill_level(), 10);
+	assert_eq!(Strength::Elo(2800).skill_level(), MAX_SKILL_LEVEL);
+	assert_eq!(Strength::Elo(100).skill_level(), 0);
+	assert_eq!(Strength::Skill(35).skill_level(), MAX_SKILL_LEVEL);
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use crate::bot::time_manager::{TimeControl, TimeManager};
+
+fn clock(remaining_secs: u64, increment_secs: u64, moves_to_go: Option<u32>) -> TimeControl {
+	TimeControl::Clock { remaining: Duration::from_secs(remaining_secs), increment: Duration::from_secs(increment_secs), moves_to_go }
+}
+
+#[test]
+fn move_time_is_used_as_is() {
+	let time_manager = TimeManager::new(&TimeControl::MoveTime(Duration::from_secs(3)));
+
+	assert_eq!(time_manager.soft_limit(), Duration::from_secs(3));
+	assert_eq!(time_manager.hard_limit(), Duration::from_secs(3));
+	assert!(!time_manager.can_stop_early());
+}
+
+#[test]
+fn clock_limits_stay_within_remaining_time() {
+	for time_control in [clock(300, 0, None), clock(60, 2, None), clock(1, 0, None), clock(10, 0, Some(1)), clock(600, 0, Some(40))] {
+		let time_manager = TimeManager::new(&time_control);
+
+		let TimeControl::Clock { remaining, .. } = time_control else { unreachable!() };
+
+		assert!(time_manager.soft_limit() <= time_manager.hard_limit());
+		assert!(time_manager.hard_limit() < remaining);
+	}
+}
+
+#[test]
+fn increment_and_moves_to_go_raise_the_limits() {
+	let base = TimeManager::new(&clock(60, 0, None));
+	let with_increment = TimeManager::new(&clock(60, 2, None));
+	let last_move = TimeManager::new(&clock(60, 0, Some(1)));
+
+	assert!(with_increment.soft_limit() > base.soft_limit());
+	assert!(last_move.hard_limit() > Duration::from_secs(50));
+}
+
+#[test]
+fn unstable_search_continues_longer() {
+	// Soft limit of about 0.5 seconds
+	let time_control = clock(15, 0, None);
+
+	let mut stable = TimeManager::new(&time_control);
+	let mut unstable = TimeManager::new(&time_control);
+
+	std::thread::sleep(Duration::from_millis(350));
+
+	assert!(stable.iteration_finished(false, 20));
+	assert!(!unstable.iteration_finished(true, 20));
+}
+
+#[test]
+fn obvious_move_is_only_checked_once_it_would_end_the_search() {
+	// Soft limit of about 0.5 seconds
+	let time_manager = TimeManager::new(&clock(15, 0, None));
+
+	assert!(!time_manager.can_stop_early());
+
+	std::thread::sleep(Duration::from_millis(100));
+
+	assert!(time_manager.can_stop_early());
+}
@@ -0,0 +1,36 @@
+use crate::{board::{moove::Move, position::Position, zobrist_hash::{generate_zobrist_numbers, ZobristHash}}, bot::transposition_table::{Bound, Transposition}};
+
+#[test]
+fn moves_survive_packing() {
+	let position = Position::from_fen_str("r3k2r/pPpp1ppp/8/3Pp3/8/8/PPPPPPPP/R3K2R w KQkq e6 0 1").unwrap();
+
+	for m in position.get_all_legal_moves() {
+		assert_eq!(Move::from_u16(m.to_u16(), position.current_player()), Some(m));
+	}
+}
+
+#[test]
+fn transposition_keeps_stored_values() {
+	let position = Position::from_fen_str("r3k2r/pPpp1ppp/8/3Pp3/8/8/PPPPPPPP/R3K2R w KQkq e6 0 1").unwrap();
+	let best_move = Move::debug_new_en_passant("d5", "e6", "e5");
+
+	let transposition = Transposition::new(1234, 7, -350, Bound::Upper, Some(&best_move));
+
+	assert!(transposition.hash_matches(1234));
+	assert!(!transposition.hash_matches(4321));
+	assert_eq!(transposition.depth(), 7);
+	assert_eq!(transposition.evaluation(), -350);
+	assert_eq!(transposition.bound(), Bound::Upper);
+	assert_eq!(transposition.best_move(position.current_player()), Some(best_move));
+}
+
+#[test]
+fn null_move_updates_hash() {
+	generate_zobrist_numbers();
+
+	let mut position = Position::from_fen_str("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2").unwrap();
+	position.make_null_move();
+
+	assert!(position.en_passant_target.is_none());
+	assert_eq!(position.hash().value(), ZobristHash::from_position(&position).value());
+}
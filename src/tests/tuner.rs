@@ -0,0 +1,49 @@
+use crate::{board::zobrist_hash::generate_zobrist_numbers, bot::eval_params::EvalParams, player::Player, tuner::{find_k, mean_error, parse_positions, tune, TunerError, TunerOptions}};
+
+#[test]
+fn positions_are_parsed_in_every_format() {
+	generate_zobrist_numbers();
+
+	let text = "\
+# comment
+
+rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 c9 \"1/2-1/2\";
+4k3/8/8/8/8/8/8/3QK3 w - - 0 40 [1.0]
+4k3/8/8/8/8/8/8/3qK3 w - - 3 40 | -850 | 0.0
+";
+	let positions = parse_positions(text).unwrap();
+
+	assert_eq!(positions.iter().map(|p| p.result).collect::<Vec<_>>(), vec![0.5, 1.0, 0.0]);
+	assert_eq!(positions[0].position.current_player(), Player::Black);
+	assert_eq!(positions[2].position.halfmove_clock(), 3);
+
+	assert!(matches!(parse_positions("4k3/8/8/8/8/8/8/3QK3 w - - 0 40 [1.0]\n4k3/8/8/8/8/8/8/3QK3 w - -\n"), Err(TunerError::InvalidLine { line: 2, .. })));
+}
+
+#[test]
+fn tuning_lowers_the_error() {
+	generate_zobrist_numbers();
+
+	// Positions won by the side with an extra knight, which the starting weights hardly value
+	let positions = parse_positions("\
+4k3/pppp4/8/8/8/2N5/PPPP4/4K3 w - - 0 1 [1.0]
+4k3/pppp4/2n5/8/8/8/PPPP4/4K3 w - - 0 1 [0.0]
+4k3/4pppp/8/3n4/8/8/4PPPP/4K3 b - - 0 1 [0.0]
+4k3/4pppp/8/8/5N2/8/4PPPP/4K3 b - - 0 1 [1.0]
+4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1 [0.5]
+").unwrap();
+
+	let mut params = EvalParams::default();
+	params.piece_values[2] = (20, 20);
+
+	let k = find_k(&params, &positions, 1);
+	let options = TunerOptions {
+		passes: 1,
+		threads: 2,
+	};
+
+	let tuned = tune(&params, &positions, &options, |_| {});
+
+	assert!(mean_error(&tuned, &positions, k, 1) < mean_error(&params, &positions, k, 1));
+	assert!(tuned.piece_values[2] >= params.piece_values[2]);
+}
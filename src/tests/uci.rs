@@ -0,0 +1,33 @@
+use crate::{board::{moove::{CastleSide, Move}, position::Position, zobrist_hash::generate_zobrist_numbers}, bot::{baseline_bots::RandomBot, eval_params::EvalParams}, piece::PieceType, player::Player, player_piece::PlayerPiece, uci::{handle_command, parse_move, UciState}};
+
+#[test]
+fn moves_are_written_in_uci_notation() {
+	assert_eq!(Move::debug_new_basic("e2", "e4").uci_string(), "e2e4");
+	assert_eq!(Move::debug_new_promoting("b7", "a8", PlayerPiece::new(Player::White, PieceType::Knight)).uci_string(), "b7a8n");
+}
+
+#[test]
+fn uci_moves_parse_to_legal_moves() {
+	let position = Position::from_fen_str("r3k2r/pPpp1ppp/8/3Pp3/8/8/PPPPPPPP/R3K2R w KQkq e6 0 1").unwrap();
+
+	for m in position.get_all_legal_moves() {
+		assert_eq!(parse_move(&position, &m.uci_string()), Some(m));
+	}
+
+	assert_eq!(parse_move(&position, "e1g1"), Some(Move::new_castling(Player::White, CastleSide::KingSide)));
+	assert_eq!(parse_move(&position, "e2e5"), None);
+}
+
+#[test]
+fn go_in_finished_game_answers_without_a_move() {
+	generate_zobrist_numbers();
+
+	let mut state = UciState::new(Box::new(RandomBot), EvalParams::default());
+	let mut out = Vec::new();
+
+	// Fool's mate
+	assert!(handle_command(&mut state, "position startpos moves f2f3 e7e5 g2g4 d8h4", &mut out).unwrap());
+	assert!(handle_command(&mut state, "go movetime 100", &mut out).unwrap());
+
+	assert_eq!(String::from_utf8(out).unwrap(), "bestmove 0000\n");
+}
@@ -0,0 +1,237 @@
+//! Texel tuning of the hand-written evaluation, used instead of the UI when started with `--tune`.
+//!
+//! Positions are read one per line with the result of their game from White's point of view, either as EPD with a
+//! `c9` opcode (`<fen> c9 "1-0";`), as a FEN followed by the result in brackets (`<fen> [0.5]`), or in the format
+//! written by [`datagen`][crate::datagen]. Empty lines and lines starting with `#` are skipped.
+//!
+//! The error of a set of parameters is the mean squared difference between the results and the evaluations turned
+//! into expected results by [`sigmoid`]. Positions are evaluated without search, so they should be quiet. Every
+//! weight of [`EvalParams::fields_mut`] is then moved up or down by one for as long as that lowers the error.
+
+use std::{fmt, fs, io, path::Path, thread};
+
+use crate::{board::position::Position, bot::eval_params::EvalParams, player::Player};
+
+/// Bounds of the search for the scaling constant of [`sigmoid`].
+const K_RANGE: (f64, f64) = (0.0, 5.0);
+const K_ITERATIONS: usize = 100;
+
+/// Position with the result of its game, 1.0 for a White win, 0.5 for a draw and 0.0 for a Black win.
+#[derive(Clone)]
+pub struct TuningPosition {
+    pub position: Position,
+    pub result: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TunerOptions {
+    /// Passes over all weights, tuning stops earlier once a pass doesn't lower the error.
+    pub passes: usize,
+    pub threads: usize,
+}
+
+impl Default for TunerOptions {
+    fn default() -> Self {
+        Self {
+            passes: 100,
+            threads: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        }
+    }
+}
+
+/// Read the positions of the file at `path`.
+pub fn load_positions(path: impl AsRef<Path>) -> Result<Vec<TuningPosition>, TunerError> {
+    let text = fs::read_to_string(path).map_err(TunerError::Io)?;
+
+    parse_positions(&text)
+}
+
+/// Parse positions in any of the formats of the module documentation.
+pub fn parse_positions(text: &str) -> Result<Vec<TuningPosition>, TunerError> {
+    let mut positions = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let position = parse_line(line).ok_or_else(|| TunerError::InvalidLine { line: index + 1, text: line.to_owned() })?;
+
+        positions.push(position);
+    }
+
+    Ok(positions)
+}
+
+fn parse_line(line: &str) -> Option<TuningPosition> {
+    // Data generation output, `<fen> | <score> | <result>`
+    if line.contains('|') {
+        let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+
+        let position = Position::from_fen_str(fields.first()?).ok()?;
+        let result = fields.get(2)?.parse().ok()?;
+
+        return Some(TuningPosition { position, result });
+    }
+
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+
+    if tokens.len() < 4 {
+        return None;
+    }
+
+    // EPD leaves out the move counters
+    let counters = tokens[4..].iter().take(2).take_while(|t| t.parse::<u32>().is_ok()).count();
+    let fen = match counters {
+        2 => tokens[..6].join(" "),
+        _ => format!("{} 0 1", tokens[..4].join(" ")),
+    };
+
+    let position = Position::from_fen_str(&fen).ok()?;
+    let result = tokens[4 + counters..].iter().find_map(|t| parse_result(t))?;
+
+    return Some(TuningPosition { position, result });
+}
+
+/// Result written in quotes or brackets, like `"1/2-1/2";` or `[0.5]`.
+fn parse_result(token: &str) -> Option<f64> {
+    if !token.contains(['"', '[']) {
+        return None;
+    }
+
+    match token.trim_matches(['"', '[', ']', ';']) {
+        "1-0" | "1.0" | "1" => Some(1.0),
+        "1/2-1/2" | "0.5" => Some(0.5),
+        "0-1" | "0.0" | "0" => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Expected result of a position evaluated at `eval` centipawns from White's point of view.
+pub fn sigmoid(eval: f64, k: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * eval / 400.0))
+}
+
+/// Evaluation of every position in centipawns from White's point of view.
+pub fn white_evaluations(params: &EvalParams, positions: &[TuningPosition], threads: usize) -> Vec<i32> {
+    let chunk_size = positions.len().div_ceil(threads.max(1)).max(1);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = positions.chunks(chunk_size).map(|chunk| scope.spawn(move || {
+            chunk.iter().map(|p| {
+                // The tables change while tuning, so the scores kept by the position can't be used
+                let eval = params.evaluate_with_pawn_table(&p.position, None, false);
+
+                if p.position.current_player() == Player::White { eval } else { -eval }
+            }).collect::<Vec<_>>()
+        })).collect();
+
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    })
+}
+
+/// Mean squared error of `params` over `positions`.
+pub fn mean_error(params: &EvalParams, positions: &[TuningPosition], k: f64, threads: usize) -> f64 {
+    let evaluations = white_evaluations(params, positions, threads);
+
+    return error_of_evaluations(&evaluations, positions, k);
+}
+
+fn error_of_evaluations(evaluations: &[i32], positions: &[TuningPosition], k: f64) -> f64 {
+    if positions.is_empty() {
+        return 0.0;
+    }
+
+    let sum: f64 = evaluations.iter().zip(positions).map(|(&eval, p)| (p.result - sigmoid(eval as f64, k)).powi(2)).sum();
+
+    return sum / positions.len() as f64;
+}
+
+/// Scaling constant of [`sigmoid`] which fits the evaluations of `params` to the results best. It is kept while
+/// tuning, so that the weights stay in centipawns.
+pub fn find_k(params: &EvalParams, positions: &[TuningPosition], threads: usize) -> f64 {
+    let evaluations = white_evaluations(params, positions, threads);
+    let (mut low, mut high) = K_RANGE;
+
+    // The error has a single minimum in k, so a ternary search finds it
+    for _ in 0..K_ITERATIONS {
+        let third = (high - low) / 3.0;
+
+        if error_of_evaluations(&evaluations, positions, low + third) < error_of_evaluations(&evaluations, positions, high - third) {
+            high -= third;
+        }
+        else {
+            low += third;
+        }
+    }
+
+    return (low + high) / 2.0;
+}
+
+/// Tune `params` on `positions` by local search and return the tuned parameters. `on_pass` is called with the
+/// parameters after every pass which lowered the error, for example to save them.
+pub fn tune(params: &EvalParams, positions: &[TuningPosition], options: &TunerOptions, mut on_pass: impl FnMut(&EvalParams)) -> EvalParams {
+    let mut params = params.clone();
+    let weight_count = weights(&mut params).len();
+
+    let k = find_k(&params, positions, options.threads);
+    let mut best_error = mean_error(&params, positions, k, options.threads);
+
+    println!("K = {:.4}, error {:.6} over {} positions", k, best_error, positions.len());
+
+    for pass in 1..=options.passes {
+        let mut improved = 0;
+
+        for index in 0..weight_count {
+            let value = *weights(&mut params)[index];
+
+            for delta in [1, -1] {
+                *weights(&mut params)[index] = value + delta;
+
+                let error = mean_error(&params, positions, k, options.threads);
+
+                if error < best_error {
+                    best_error = error;
+                    improved += 1;
+                    break;
+                }
+
+                *weights(&mut params)[index] = value;
+            }
+        }
+
+        println!("Pass {}: error {:.6}, {} of {} weights changed", pass, best_error, improved, weight_count);
+
+        if improved == 0 {
+            break;
+        }
+
+        on_pass(&params);
+    }
+
+    params
+}
+
+/// Every weight of `params` in the order of [`EvalParams::fields_mut`].
+fn weights(params: &mut EvalParams) -> Vec<&mut i32> {
+    params.fields_mut().into_iter().flat_map(|(_, values)| values).collect()
+}
+
+/// Reason positions couldn't be loaded.
+#[derive(Debug)]
+pub enum TunerError {
+    Io(io::Error),
+    /// A line with no position and result in any of the known formats.
+    InvalidLine { line: usize, text: String },
+}
+
+impl fmt::Display for TunerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "couldn't read positions: {}", error),
+            Self::InvalidLine { line, text } => write!(f, "invalid position on line {}: {}", line, text),
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Universal Chess Interface frontend, used instead of the UI when started with `--uci`.
+
+use std::{io::{self, BufRead, Write}, sync::mpsc::{self, RecvTimeoutError}, thread, time::Duration};
+
+use crate::{analysis::evaluation_lines, board::{moove::Move, position::Position}, bot::{eval_params::EvalParams, search_limits::{PonderHit, SearchLimits}, search_observer::{SearchEvent, SearchInfo}, time_manager::TimeControl, Bot}, player::Player, r#match::Match};
+
+const ENGINE_NAME: &str = "Chesster";
+/// How often a running search is checked for a result while waiting for commands.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+pub struct UciState {
+    game_match: Match,
+    /// An infinite search keeps its move until `stop` arrives, even if it finished earlier.
+    infinite: bool,
+    /// A pondering search keeps its move until `ponderhit` or `stop` arrives.
+    ponder: Option<PonderHit>,
+    /// Weights whose terms the `eval` command prints.
+    eval_params: EvalParams,
+}
+
+/// Read UCI commands from stdin and answer on stdout until `quit`. Besides the standard commands, `eval` prints the
+/// terms of the evaluation with `eval_params` of the current position.
+pub fn start_uci(bot: Box<dyn Bot>, eval_params: EvalParams) {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut state = UciState::new(bot, eval_params);
+
+    loop {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(line) => {
+                if !matches!(handle_command(&mut state, &line, &mut io::stdout()), Ok(true)) {
+                    break;
+                }
+            },
+            Err(RecvTimeoutError::Timeout) => {},
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        for event in state.game_match.take_search_events() {
+            match event {
+                SearchEvent::Iteration(info) => println!("{}", info_string(&info)),
+                SearchEvent::BookMove(moove) => println!("info string book move {}", moove.uci_string()),
+            }
+        }
+
+        if !state.infinite && state.ponder.is_none() && state.game_match.bot_move_available() {
+            let result = state.game_match.get_searched_move();
+
+            match result.ponder_move() {
+                Some(ponder_move) => println!("bestmove {} ponder {}", result.best_move.uci_string(), ponder_move.uci_string()),
+                None => println!("bestmove {}", result.best_move.uci_string()),
+            }
+        }
+    }
+
+    state.game_match.abort_search();
+}
+
+impl UciState {
+    /// State before any command, with `bot` playing both sides.
+    pub fn new(bot: Box<dyn Bot>, eval_params: EvalParams) -> Self {
+        Self {
+            game_match: Match::new(&Position::default(), Some(dyn_clone::clone_box(&*bot)), Some(bot), TimeControl::MoveTime(Duration::ZERO)),
+            infinite: false,
+            ponder: None,
+            eval_params,
+        }
+    }
+}
+
+/// Search progress as an `info` line.
+fn info_string(info: &SearchInfo) -> String {
+    let pv: Vec<String> = info.pv.iter().map(|m| m.uci_string()).collect();
+
+    format!("info depth {} seldepth {} multipv {} score {} nodes {} nps {} hashfull {} time {} pv {}", info.depth, info.seldepth, info.multi_pv, info.score.uci_string(), info.nodes, info.nps, info.hashfull, info.time.as_millis(), pv.join(" "))
+}
+
+/// Handle a single command, writing the immediate answers to `out`. Returns `false` on `quit`.
+pub fn handle_command(state: &mut UciState, line: &str, out: &mut impl Write) -> io::Result<bool> {
+    let mut tokens = line.split_whitespace();
+
+    match tokens.next() {
+        Some("uci") => {
+            writeln!(out, "id name {}", ENGINE_NAME)?;
+            writeln!(out, "option name Ponder type check default false")?;
+
+            for option in state.game_match.bot_options() {
+                writeln!(out, "{}", option.uci_string())?;
+            }
+
+            writeln!(out, "uciok")?;
+        },
+        Some("isready") => writeln!(out, "readyok")?,
+        Some("ucinewgame") => state.game_match.new_game(&Position::default()),
+        Some("setoption") => {
+            if let Err(message) = set_option(&mut state.game_match, tokens.collect()) {
+                writeln!(out, "info string {}", message)?;
+            }
+        },
+        Some("position") => {
+            state.infinite = false;
+            state.ponder = None;
+
+            if let Err(message) = set_position(&mut state.game_match, tokens.collect()) {
+                writeln!(out, "info string {}", message)?;
+            }
+        },
+        Some("go") => {
+            let limits = parse_limits(tokens.collect(), state.game_match.position().current_player());
+
+            state.infinite = limits.infinite;
+            state.ponder = limits.ponder.clone();
+            state.game_match.start_search(limits);
+
+            // The game is already over, so there is no move to search
+            if !state.game_match.is_searching() {
+                state.infinite = false;
+                state.ponder = None;
+                writeln!(out, "bestmove 0000")?;
+            }
+        },
+        Some("ponderhit") => {
+            if let Some(ponder_hit) = state.ponder.take() {
+                ponder_hit.trigger();
+            }
+        },
+        Some("stop") => {
+            state.infinite = false;
+            state.ponder = None;
+            state.game_match.stop_search();
+        },
+        Some("eval") => {
+            for line in evaluation_lines(&state.eval_params, state.game_match.position()) {
+                writeln!(out, "{}", line)?;
+            }
+        },
+        Some("quit") => return Ok(false),
+        _ => {},
+    }
+
+    Ok(true)
+}
+
+/// Handle `position [startpos | fen <fen>] [moves <move>...]`.
+fn set_position(game_match: &mut Match, tokens: Vec<&str>) -> Result<(), String> {
+    let moves_index = tokens.iter().position(|t| *t == "moves").unwrap_or(tokens.len());
+
+    let position = match tokens.first() {
+        Some(&"startpos") => Position::default(),
+        Some(&"fen") => {
+            let fen = tokens[1..moves_index].join(" ");
+
+            Position::from_fen_str(&fen).map_err(|e| format!("invalid fen {}: {:?}", fen, e))?
+        },
+        _ => return Err("expected startpos or fen".to_owned()),
+    };
+
+    game_match.set_position(&position);
+
+    for move_str in tokens.iter().skip(moves_index + 1) {
+        let Some(moove) = parse_move(game_match.position(), move_str) else {
+            return Err(format!("illegal move {}", move_str));
+        };
+
+        game_match.push_move(moove);
+    }
+
+    Ok(())
+}
+
+/// Handle `setoption name <name> [value <value>]`. Names and values may contain spaces.
+fn set_option(game_match: &mut Match, tokens: Vec<&str>) -> Result<(), String> {
+    if tokens.first() != Some(&"name") {
+        return Err("expected name".to_owned());
+    }
+
+    let value_index = tokens.iter().position(|t| *t == "value").unwrap_or(tokens.len());
+    let name = tokens[1..value_index].join(" ");
+    let value = tokens.get(value_index + 1..).map_or(String::new(), |v| v.join(" "));
+
+    // The GUI handles pondering, the bots don't have an option for it
+    if name == "Ponder" {
+        return Ok(());
+    }
+
+    game_match.set_bot_option(&name, &value).map_err(|e| e.to_string())
+}
+
+/// Find the legal move written as `move_str` in UCI notation.
+pub fn parse_move(position: &Position, move_str: &str) -> Option<Move> {
+    position.get_all_legal_moves().into_iter().find(|m| m.uci_string() == move_str)
+}
+
+/// Parse the arguments of `go` for `player`.
+fn parse_limits(tokens: Vec<&str>, player: Player) -> SearchLimits {
+    let value = |name: &str| tokens.iter().position(|t| *t == name).and_then(|i| tokens.get(i + 1)).and_then(|v| v.parse::<u64>().ok());
+    let millis = |name: &str| value(name).map(Duration::from_millis);
+
+    let (remaining, increment) = match player {
+        Player::White => (millis("wtime"), millis("winc")),
+        Player::Black => (millis("btime"), millis("binc")),
+    };
+
+    let time_control = if let Some(move_time) = millis("movetime") {
+        Some(TimeControl::MoveTime(move_time))
+    }
+    else if let Some(remaining) = remaining {
+        Some(TimeControl::Clock {
+            remaining,
+            increment: increment.unwrap_or(Duration::ZERO),
+            moves_to_go: value("movestogo").map(|m| m as u32),
+        })
+    }
+    else {
+        None
+    };
+
+    SearchLimits {
+        time_control,
+        depth: value("depth").map(|d| d as u32),
+        nodes: value("nodes"),
+        mate: value("mate").map(|m| m as u32),
+        infinite: tokens.contains(&"infinite"),
+        ponder: tokens.contains(&"ponder").then(PonderHit::new),
+    }
+}
@@ -5,16 +5,16 @@ pub mod texture;
 pub mod text_area;
 pub mod ui;
 
-use std::time::Duration;
-
 use ui::UI;
 
-use crate::{board::position::Position, bot::{evaluation_funcs::evaluate_phase_and_bishop_pair_and_rook_open_column, iterative_deepening_search::IterativeDeepeningSearch, Bot}, r#match::Match};
+use crate::{board::position::Position, bot::{eval_params::EvalParams, time_manager::TimeControl, Bot}, r#match::Match};
 
 const WINDOW_WIDTH: i32 = 1280;
 const WINDOW_HEIGHT: i32 = 720;
 
-pub fn start_ui(white_bot: bool, black_bot: bool) {
+/// Open the window. Players without a bot are played by the user. Bots think on the opponent's time if `ponder` is set.
+/// The debug panel shows the evaluation terms with `eval_params`.
+pub fn start_ui(white_bot: Option<Box<dyn Bot>>, black_bot: Option<Box<dyn Bot>>, time_control: TimeControl, ponder: bool, eval_params: EvalParams) {
 	let (mut rl, thread) = raylib::init()
 		.vsync()
 		.size(WINDOW_WIDTH, WINDOW_HEIGHT)
@@ -24,12 +24,10 @@ pub fn start_ui(white_bot: bool, black_bot: bool) {
 
 	let position = Position::default();
 
-	let white_bot: Option<Box<dyn Bot>> = if white_bot { Some(Box::new(IterativeDeepeningSearch::new(evaluate_phase_and_bishop_pair_and_rook_open_column, true))) } else { None };
-	let black_bot: Option<Box<dyn Bot>> = if black_bot { Some(Box::new(IterativeDeepeningSearch::new(evaluate_phase_and_bishop_pair_and_rook_open_column, true))) } else { None };
-
-	let game_match = Match::new(&position, white_bot, black_bot, Duration::from_secs(5));
+	let mut game_match = Match::new(&position, white_bot, black_bot, time_control);
+	game_match.set_pondering(ponder);
 
-	let mut ui = UI::new(&mut rl, &thread, game_match);
+	let mut ui = UI::new(&mut rl, &thread, game_match, eval_params);
 
 	while !rl.window_should_close() {
 		ui.handle_input(&rl);
@@ -1,7 +1,7 @@
 
 use raylib::{color::Color, ffi::{KeyboardKey, MouseButton}, prelude::{RaylibDraw, RaylibDrawHandle}, RaylibHandle, RaylibThread};
 
-use crate::{board::{game_state::GameState, moove::{Move, PromotingMove}, tile_position::TilePosition}, r#match::Match, piece::PieceType, player::Player, player_piece::PlayerPiece};
+use crate::{board::{game_state::GameState, moove::{Move, PromotingMove}, tile_position::TilePosition}, bot::{bot_option::OptionKind, eval_params::EvalParams, eval_trace::EvalTrace, limited_strength::{LIMIT_STRENGTH_OPTION, MAX_SKILL_LEVEL, SKILL_LEVEL_OPTION}, score::Score, search_observer::{SearchEvent, SearchInfo}}, r#match::Match, piece::PieceType, player::Player, player_piece::PlayerPiece};
 
 use super::{board_renderer::BoardRenderer, text_area::TextArea, texture::{load_circle_texture, load_piece_textures}};
 
@@ -11,7 +11,10 @@ pub struct UI {
 	game_match: Match,
 	viewed_position: usize,
 
+	/// Shows the terms of the evaluation of the viewed position.
 	is_debug: bool,
+	/// Weights the debug panel evaluates with.
+	eval_params: EvalParams,
 
 	hovered_tile: Option<TilePosition>,
 	selected_tile: Option<TilePosition>,
@@ -20,10 +23,15 @@ pub struct UI {
 
 	promotion_menu_open: bool,
 	promoting_move: Option<PromotingMove>,
+
+	/// Score of the last bot move with the player who made it.
+	last_bot_score: Option<(Player, Score)>,
+	/// Lines of the latest completed iteration of the running or last finished search, best first.
+	search_lines: Vec<SearchInfo>,
 }
 
 impl UI {
-	pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread, mut game_match: Match) -> Self {
+	pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread, mut game_match: Match, eval_params: EvalParams) -> Self {
 		let piece_textures = load_piece_textures(rl, thread);
 		let circle_texture = load_circle_texture(rl, thread);
 		let mut board_renderer = BoardRenderer::new(0, 0, rl.get_screen_height(), 32, Player::White, piece_textures, circle_texture);
@@ -39,12 +47,15 @@ impl UI {
 			board_renderer,
 			game_match,
 			is_debug: false,
+			eval_params,
 			hovered_tile: None,
 			selected_tile: None,
 			background_color: Color { r: 0, g: 65, b: 119, a: 255 },
 			promotion_menu_open: false,
 			promoting_move: None,
 			viewed_position: 0,
+			last_bot_score: None,
+			search_lines: Vec::new(),
 		}
 	}
 
@@ -58,12 +69,30 @@ impl UI {
 	}
 
 	pub fn make_bot_move_if_ready(&mut self) {
+		for event in self.game_match.take_search_events() {
+			match event {
+				SearchEvent::Iteration(info) => {
+					println!("{}", info);
+
+					// The first line starts a new iteration
+					if info.multi_pv == 1 {
+						self.search_lines.clear();
+					}
+
+					self.search_lines.push(info);
+				},
+				SearchEvent::BookMove(m) => println!("Book move: {}", m.debug_string()),
+			}
+		}
+
 		if self.game_match.bot_move_available() {
-			let moove = self.game_match.get_searched_move();
+			let result = self.game_match.get_searched_move();
+
+			println!("{} | {}", result.best_move.debug_string(), result.score);
 
-			println!("{} | {}", moove.1.debug_string(), moove.0);
+			self.last_bot_score = Some((self.game_match.position().current_player(), result.score));
 
-			self.play_move(moove.1);
+			self.play_move(result.best_move);
 		}
 	}
 
@@ -83,6 +112,13 @@ impl UI {
 
 		self.text_area.draw_line(draw_handle, format!("Current player: {}", &player_str).as_str());
 
+		for player in [Player::White, Player::Black] {
+			if let Some(remaining) = self.game_match.remaining_time(player) {
+				let seconds = remaining.as_secs();
+				self.text_area.draw_line(draw_handle, &format!("{} clock: {}:{:02}", player.as_str(), seconds / 60, seconds % 60));
+			}
+		}
+
 
 		if let Some(hovered_tile) = self.hovered_tile {
 			self.text_area.draw_line(draw_handle, &hovered_tile.notation_string());
@@ -108,6 +144,28 @@ impl UI {
 			let player_str = self.game_match.position().current_player().as_str();
 			self.text_area.draw_line(draw_handle, &format!("{} is thinking...", player_str));
 		}
+		else if self.game_match.is_pondering() {
+			let player_str = self.game_match.position().current_player().opposite().as_str();
+			self.text_area.draw_line(draw_handle, &format!("{} is pondering...", player_str));
+		}
+
+		if let Some(skill_level) = self.skill_level() {
+			self.text_area.draw_line(draw_handle, &format!("Skill level: {} (Up/Down)", skill_level));
+		}
+
+		if let Some((player, score)) = self.last_bot_score {
+			self.text_area.draw_line(draw_handle, &format!("{} eval: {}", player.as_str(), score));
+		}
+
+		if let Some(info) = self.search_lines.first() {
+			self.text_area.draw_line(draw_handle, &format!("Depth: {}/{} Nodes: {} ({} nps)", info.depth, info.seldepth, info.nodes, info.nps));
+		}
+
+		for info in &self.search_lines {
+			let pv: Vec<String> = info.pv.iter().map(|m| m.uci_string()).collect();
+
+			self.text_area.draw_line(draw_handle, &format!("{}. {} {}", info.multi_pv, info.score, pv.join(" ")));
+		}
 
 		if self.viewed_position != 0 {
 			self.text_area.draw_line(draw_handle, &format!("Viewing position: -{}", self.viewed_position));
@@ -121,6 +179,14 @@ impl UI {
 			self.text_area.draw_line(draw_handle, "4: Bishop");
 		}
 
+		if self.is_debug {
+			if let Some(position) = self.game_match.get_position_n_moves_ago(self.viewed_position) {
+				for line in EvalTrace::new(&self.eval_params, position).lines() {
+					self.text_area.draw_line(draw_handle, &line);
+				}
+			}
+		}
+
 		self.text_area.reset();
 	}
 
@@ -150,6 +216,18 @@ impl UI {
 			}
 		}
 
+		if rl.is_key_pressed(KeyboardKey::KEY_D) {
+			self.is_debug = !self.is_debug;
+		}
+
+		if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+			self.change_skill_level(1);
+		}
+
+		if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+			self.change_skill_level(-1);
+		}
+
 		if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
 			self.game_match.undo_to_n_moves_ago(self.viewed_position);
 			self.board_renderer.set_last_move(None);
@@ -335,6 +413,29 @@ impl UI {
 		}
 	}
 
+	/// Skill level of the bots, [`None`] if they can't be weakened.
+	fn skill_level(&self) -> Option<i64> {
+		let option = self.game_match.bot_options().into_iter().find(|o| o.name == SKILL_LEVEL_OPTION)?;
+
+		match option.kind {
+			OptionKind::Spin { value, .. } => Some(value),
+			_ => None,
+		}
+	}
+
+	/// Make the bots play `change` skill levels stronger. Applies from their next move on.
+	fn change_skill_level(&mut self, change: i64) {
+		let Some(skill_level) = self.skill_level() else {
+			return;
+		};
+
+		let skill_level = (skill_level + change).clamp(0, MAX_SKILL_LEVEL as i64);
+
+		// A strength set as Elo would take precedence
+		let _ = self.game_match.set_bot_option(LIMIT_STRENGTH_OPTION, "false");
+		let _ = self.game_match.set_bot_option(SKILL_LEVEL_OPTION, &skill_level.to_string());
+	}
+
 	fn toggle_board_perspective(&mut self) {
 		self.board_renderer.swap_player();
 	}